@@ -1,14 +1,22 @@
 use clap::App;
 
+use crate::loader::LoadPolicy;
+
 pub enum Mode {
     TUI,
     Background(u64),
     Video,
+    Prefetch,
 }
 
 pub struct Arguments {
     pub mode: Mode,
-    pub yaml: String,
+    pub yaml: Vec<String>,
+    // Manual alphabet replacing the built-in one
+    pub alphabet: Option<String>,
+    // Rules of the numbers replacing the built-in ones
+    pub numbers: Option<String>,
+    pub policy: LoadPolicy,
     pub video_word: String,
     pub description: bool,
 }
@@ -17,8 +25,16 @@ pub fn parse_arguments() -> Arguments {
     // arguments
     let cli_yaml = load_yaml!("../cli.yml");
     let matches = App::from_yaml(cli_yaml).get_matches();
-    let lsf_yaml = matches.value_of("yaml").unwrap_or("LSF.yaml");
+    let lsf_yaml = matches
+        .values_of("yaml")
+        .map(|values| values.map(String::from).collect())
+        .unwrap_or_else(|| vec![String::from("LSF.yaml")]);
     let description = matches.is_present("description");
+    let policy = if matches.is_present("skip-invalid") {
+        LoadPolicy::Lenient
+    } else {
+        LoadPolicy::Strict
+    };
     let video_word = matches.value_of("video").unwrap_or("bonjour");
 
     let mode = if matches.is_present("background") {
@@ -27,13 +43,18 @@ pub fn parse_arguments() -> Arguments {
         Mode::Background(int_seconds)
     } else if matches.is_present("video") {
         Mode::Video
+    } else if matches.is_present("prefetch") {
+        Mode::Prefetch
     }else {
         Mode::TUI
     };
 
     Arguments {
         mode,
-        yaml: lsf_yaml.to_string(),
+        yaml: lsf_yaml,
+        alphabet: matches.value_of("alphabet").map(String::from),
+        numbers: matches.value_of("numbers").map(String::from),
+        policy,
         video_word: video_word.to_string(),
         description,
     }
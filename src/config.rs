@@ -0,0 +1,91 @@
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use yaml_rust::Yaml;
+
+use crate::search_video::VideoOutput;
+use crate::storage::{config_dir, load_yaml};
+use crate::video_frame::Graphics;
+use crate::video_source::{ElixSource, LinkSource, LocalSource, VideoSource, ELIX_URL};
+
+// Settings of the configuration file, every key is optional:
+//
+// video:
+//   source: elix    # elix, local or link
+//   url: "https://dico.elix-lsf.fr"
+//   directory: "~/Videos/LSF"
+//   output: terminal  # terminal or window
+//   graphics: auto    # auto, halfblocks, sixel or kitty
+pub struct Config {
+    video_source: String,
+    elix_url: String,
+    video_dir: Option<PathBuf>,
+    video_output: String,
+    graphics: String,
+    path: PathBuf,
+}
+
+impl Config {
+    pub fn load() -> Result<Self, io::Error> {
+        let path = config_dir().join("config.yaml");
+        let doc = load_yaml(&path)?.unwrap_or(Yaml::Null);
+        let video = &doc["video"];
+
+        Ok(Self {
+            video_source: video["source"].as_str().unwrap_or("elix").to_string(),
+            elix_url: video["url"].as_str().unwrap_or(ELIX_URL).to_string(),
+            video_dir: video["directory"].as_str().map(expand_home),
+            video_output: video["output"].as_str().unwrap_or("terminal").to_string(),
+            graphics: video["graphics"].as_str().unwrap_or("auto").to_string(),
+            path,
+        })
+    }
+
+    // Provider of the videos selected by the configuration
+    pub fn video_source(&self) -> Result<Arc<dyn VideoSource>, io::Error> {
+        match self.video_source.as_str() {
+            "elix" => Ok(Arc::new(ElixSource::new(&self.elix_url))),
+            "local" => match &self.video_dir {
+                Some(dir) => Ok(Arc::new(LocalSource::new(dir.to_path_buf()))),
+                None => Err(self.error("video.directory is required by the local source")),
+            },
+            "link" => Ok(Arc::new(LinkSource)),
+            other => Err(self.error(&format!(
+                "unknown video source '{}', expected elix, local or link",
+                other
+            ))),
+        }
+    }
+
+    // Where videos are played, and how in the terminal
+    pub fn video_output(&self) -> Result<VideoOutput, io::Error> {
+        match self.video_output.as_str() {
+            "window" => Ok(VideoOutput::Window),
+            "terminal" => match Graphics::from_name(&self.graphics) {
+                Some(graphics) => Ok(VideoOutput::Terminal(graphics)),
+                None => Err(self.error(&format!(
+                    "unknown graphics '{}', expected auto, halfblocks, sixel or kitty",
+                    self.graphics
+                ))),
+            },
+            other => Err(self.error(&format!(
+                "unknown video output '{}', expected terminal or window",
+                other
+            ))),
+        }
+    }
+
+    fn error(&self, message: &str) -> io::Error {
+        let info = format!("{}: {}", self.path.display(), message);
+        io::Error::new(io::ErrorKind::InvalidData, info)
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => PathBuf::from(env::var_os("HOME").unwrap_or_default()).join(rest),
+        None => PathBuf::from(path),
+    }
+}
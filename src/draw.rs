@@ -1,31 +1,94 @@
 use std::convert::TryFrom;
 
 use std::io;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // tui
-use tui::backend::TermionBackend;
+use tui::backend::{Backend, TermionBackend};
 use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use tui::style::{Color, Modifier, Style};
-use tui::symbols::DOT;
+use tui::symbols::{Marker, DOT};
 use tui::text::{Span, Spans};
-use tui::widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Tabs, Wrap};
-use tui::Terminal;
+use tui::widgets::{
+    Axis, BarChart, Block, Borders, Chart, Clear, Dataset, Gauge, GraphType, List, ListItem,
+    Paragraph, Sparkline, Tabs, Wrap,
+};
+use tui::{Frame, Terminal};
 
 // local modules
-use crate::loader::{Categorie, Word};
+use crate::favourites::Favourites;
+use crate::high_scores::HighScores;
+use crate::loader::{Categorie, Parameter, Word};
+use crate::numbers::BOUNDS;
+use crate::quiz::{Edit, LearnMode, Verdict};
+use crate::scheduler::{CardDirection, Grade};
+use crate::search_video::{VideoPlayer, VideoStatus};
+use crate::video_frame::{write_image, Graphics, HalfBlocks};
+use crate::video_source::Meaning;
 use crate::selection::Selection;
+use crate::session::Session;
+use crate::setup::{SessionSetup, WordSource, SIZES, TIMERS};
+use crate::spelling::{Spelling, SpellingSource, SPEEDS};
+use crate::stats::{Statistics, HISTORY_DAYS};
+use crate::storage::today;
 
 #[derive(PartialEq)]
 pub enum WordState {
-    Valided,
-    Failed,
+    // Grade given by the learner, its time in seconds since epoch
+    // and the time spent on the word
+    Graded(Grade, u64, Duration),
+    // Word of a quiz whose sign could not be played, left ungraded
+    // with the time spent on it
+    Skipped(Duration),
     Current,
+    // Current word whose help has been displayed
+    Peeked,
     Next,
 }
 
-// Number of words to learn for a session in tab 'Learn'
-// pub static WORDS_LEARN_SIZE: usize = 20;
+impl WordState {
+    pub fn is_failed(&self) -> bool {
+        matches!(self, WordState::Graded(Grade::Again, ..))
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        matches!(self, WordState::Skipped(_))
+    }
+
+    // Time spent on a graded or skipped word
+    pub fn spent(&self) -> Option<Duration> {
+        match self {
+            WordState::Graded(_, _, spent) | WordState::Skipped(spent) => Some(*spent),
+            _ => None,
+        }
+    }
+}
+
+// Keys used to grade a word in tab 'Learn'
+pub static GRADE_KEYS: [(char, Grade); 4] = [
+    ('a', Grade::Again),
+    ('s', Grade::Hard),
+    ('d', Grade::Good),
+    ('f', Grade::Easy),
+];
+
+fn grade_label(grade: Grade) -> &'static str {
+    match grade {
+        Grade::Again => "Again",
+        Grade::Hard => "Hard",
+        Grade::Good => "Good",
+        Grade::Easy => "Easy",
+    }
+}
+
+fn grade_style(grade: Grade) -> Style {
+    match grade {
+        Grade::Again => Style::default().bg(Color::Red),
+        Grade::Hard => Style::default().bg(Color::Yellow).fg(Color::Black),
+        Grade::Good => Style::default().bg(Color::Green),
+        Grade::Easy => Style::default().bg(Color::Blue),
+    }
+}
 
 // Function from tui-rs example source
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -54,13 +117,500 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
+// Lines describing the parameters of the sign, if any
+fn parameters_text(word: &Word) -> Vec<Spans<'_>> {
+    let mut text = vec![];
+    for parameter in Parameter::ALL.iter() {
+        if let Some(value) = word.parameters.get(*parameter) {
+            if text.is_empty() {
+                text.push(Spans::from(Span::raw("")));
+            }
+            text.push(Spans::from(vec![
+                Span::styled(
+                    format!("{} : ", parameter.label()),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(value, Style::default().fg(Color::Magenta)),
+            ]));
+        }
+    }
+    text
+}
+
+fn format_duration(time: &Duration) -> String {
+    format!("{}.{} seconds", time.as_secs(), time.as_millis() / 100 % 10)
+}
+
+// Number of slowest words displayed in the session summary
+const SLOWEST_WORDS: usize = 3;
+
+// Content of the pop-up displayed at the end of a session,
+// from the answers of its first round
+fn summary_text<'a>(
+    words_learn_set: &[(&'a Word, WordState)],
+    time: &Duration,
+    rounds: usize,
+) -> Vec<Spans<'a>> {
+    let title = Style::default().add_modifier(Modifier::BOLD);
+    let failed: Vec<&Word> = words_learn_set
+        .iter()
+        .filter(|(_, state)| state.is_failed())
+        .map(|(word, _)| *word)
+        .collect();
+    let skipped = words_learn_set.iter().filter(|(_, state)| state.is_skipped()).count();
+    let validated = words_learn_set.len() - failed.len() - skipped;
+    let average = *time / u32::try_from(words_learn_set.len()).unwrap_or(1).max(1);
+
+    let mut text = vec![
+        Spans::from(vec![
+            Span::styled(
+                format!("{} validated", validated),
+                Style::default().fg(Color::Green),
+            ),
+            Span::raw(" - "),
+            Span::styled(
+                format!("{} failed", failed.len()),
+                Style::default().fg(Color::Red),
+            ),
+        ]),
+        Spans::from(Span::raw(format!(
+            "Total {} - {} per word",
+            format_duration(time),
+            format_duration(&average)
+        ))),
+        Spans::from(Span::raw("")),
+        Spans::from(Span::styled("Slowest words", title)),
+    ];
+    if rounds > 1 {
+        text.insert(
+            2,
+            Spans::from(Span::raw(format!("Every word validated after {} rounds", rounds))),
+        );
+    }
+
+    if skipped > 0 {
+        text[0].0.push(Span::raw(" - "));
+        text[0].0.push(Span::styled(
+            format!("{} skipped, without video", skipped),
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    }
+
+    let mut slowest: Vec<(&Word, Duration)> = words_learn_set
+        .iter()
+        .filter(|(_, state)| !state.is_skipped())
+        .filter_map(|(word, state)| state.spent().map(|spent| (*word, spent)))
+        .collect();
+    slowest.sort_by_key(|(_, spent)| std::cmp::Reverse(*spent));
+    for (word, spent) in slowest.iter().take(SLOWEST_WORDS) {
+        text.push(Spans::from(Span::raw(format!(
+            "{} ({})",
+            word.name,
+            format_duration(spent)
+        ))));
+    }
+
+    if !failed.is_empty() {
+        text.push(Spans::from(Span::raw("")));
+        text.push(Spans::from(Span::styled("Failed words", title)));
+        for word in failed.iter() {
+            text.push(Spans::from(Span::styled(
+                word.name.to_string(),
+                Style::default().fg(Color::Red),
+            )));
+        }
+    }
+
+    text.push(Spans::from(Span::raw("")));
+    let mut actions = vec![];
+    if !failed.is_empty() {
+        actions.push(Span::styled("r", title));
+        actions.push(Span::raw(" Retry failed words  "));
+    }
+    actions.push(Span::styled("n", title));
+    actions.push(Span::raw(" New shuffle  "));
+    actions.push(Span::styled("1", title));
+    actions.push(Span::raw(" Dictionary"));
+    text.push(Spans::from(actions));
+
+    text
+}
+
+// Status of the video lookup, with a spinner while it is running,
+// and the playback controls while a video is played
+fn video_text(video: &VideoPlayer) -> Option<Vec<Spans<'_>>> {
+    const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+    let frame = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() / 200)
+        .unwrap_or(0) as usize;
+    let spinner = Span::styled(
+        SPINNER[frame % SPINNER.len()],
+        Style::default().fg(Color::Yellow),
+    );
+
+    // The word is not shown in the recognition quiz
+    let name = |word: &str| {
+        if video.hidden {
+            String::from("the sign")
+        } else {
+            word.to_string()
+        }
+    };
+
+    match &video.status {
+        VideoStatus::Idle => None,
+        VideoStatus::Searching(word) => Some(vec![Spans::from(vec![
+            spinner,
+            Span::raw(format!(" Searching videos of {}", name(word))),
+        ])]),
+        VideoStatus::Playing(word) => {
+            let playback = &video.playback;
+            let mut state = vec![format!("{}x", playback.rate)];
+            if playback.looping {
+                state.push(String::from("loop"));
+            }
+            if playback.paused {
+                state.push(String::from("paused"));
+            }
+            Some(vec![
+                Spans::from(vec![
+                    spinner,
+                    Span::raw(format!(" Playing {} ", name(word))),
+                    Span::styled(
+                        format!("[{}]", state.join(", ")),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                ]),
+                Spans::from(Span::styled(
+                    if video.hidden {
+                        "ctrl-r play again"
+                    } else {
+                        "space pause, -/+ speed, o loop, . next frame, x stop"
+                    },
+                    Style::default().add_modifier(Modifier::DIM),
+                )),
+            ])
+        }
+        VideoStatus::NotFound(word) => Some(vec![Spans::from(Span::styled(
+            format!("No video found for {}", name(word)),
+            Style::default().fg(Color::Yellow),
+        ))]),
+        VideoStatus::Failed(word, e) => Some(vec![Spans::from(Span::styled(
+            format!("{}: {}", name(word), e),
+            Style::default().fg(Color::Red),
+        ))]),
+    }
+}
+
+// Correction of a wrong answer: letters to remove are crossed out
+// and letters to add are underlined
+fn correction_spans(edits: &[Edit]) -> Vec<Span<'static>> {
+    let extra = Style::default()
+        .fg(Color::Red)
+        .add_modifier(Modifier::CROSSED_OUT);
+    let missing = Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    for edit in edits {
+        match *edit {
+            Edit::Same(c) => spans.push(Span::raw(c.to_string())),
+            Edit::Extra(c) => spans.push(Span::styled(c.to_string(), extra)),
+            Edit::Missing(c) => spans.push(Span::styled(c.to_string(), missing)),
+            Edit::Wrong(typed, expected) => {
+                spans.push(Span::styled(typed.to_string(), extra));
+                spans.push(Span::styled(expected.to_string(), missing));
+            }
+        }
+    }
+    spans
+}
+
+// Prompt of the recognition quiz, of typed mode and of the multiple
+// choice quiz, with the words of the session matching the answer or
+// the candidate words, and the result of the previous answer
+fn answer_text<'a>(session: &'a Session, word: &'a Word) -> Vec<Spans<'a>> {
+    let answer = &session.answer;
+    let mut text = Vec::new();
+
+    if let Some(feedback) = &answer.feedback {
+        let (label, color) = match feedback.verdict {
+            Verdict::Right => ("Right", Color::Green),
+            Verdict::Typo => ("Almost", Color::Yellow),
+            Verdict::Wrong => ("Wrong", Color::Red),
+        };
+        text.push(Spans::from(Span::styled(
+            format!("{}, {}", label, feedback.word),
+            Style::default().fg(color),
+        )));
+        if feedback.verdict != Verdict::Right && !feedback.edits.is_empty() {
+            text.push(Spans::from(correction_spans(&feedback.edits)));
+        }
+        text.push(Spans::from(Span::raw("")));
+    }
+
+    if session.mode != LearnMode::Recognition {
+        text.push(if word.description.is_empty() {
+            Spans::from(Span::styled(
+                if session.mode == LearnMode::Choice {
+                    "No description, watch the sign"
+                } else {
+                    "No description, press ctrl-r to see the sign"
+                },
+                Style::default().add_modifier(Modifier::DIM),
+            ))
+        } else {
+            Spans::from(Span::styled(
+                word.description.to_string(),
+                Style::default().fg(Color::Red),
+            ))
+        });
+    }
+    text.push(Spans::from(Span::styled(
+        match session.mode {
+            LearnMode::Typed => "Type the word",
+            LearnMode::Choice => "Which word is it?",
+            _ => "Which word is signed?",
+        },
+        Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::BOLD),
+    )));
+    if session.mode == LearnMode::Choice {
+        for (i, choice) in answer.choices.iter().enumerate() {
+            text.push(Spans::from(vec![
+                Span::styled(
+                    format!("{} ", i + 1),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(choice.to_string()),
+            ]));
+        }
+    } else {
+        text.push(Spans::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Yellow)),
+            Span::raw(answer.text.to_string()),
+            Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+        ]));
+    }
+    if session.mode == LearnMode::Recognition {
+        for (i, suggestion) in answer.suggestions(&session.words).into_iter().enumerate() {
+            let style = if answer.choice == Some(i) {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            text.push(Spans::from(Span::styled(suggestion, style)));
+        }
+    }
+    text.push(Spans::from(Span::raw("")));
+    text.push(Spans::from(Span::styled(
+        match session.mode {
+            LearnMode::Typed => "enter check, ctrl-r sign, esc recall words",
+            LearnMode::Choice => "1-4 pick, v sign, esc recall words",
+            _ => "enter check, up/down pick, esc recall words",
+        },
+        Style::default().add_modifier(Modifier::DIM),
+    )));
+    text
+}
+
+// List of the videos of the picker, the first video
+// of each meaning is preceded by the meaning and its definition
+fn video_picker_list<'a>(word: &str, meanings: &'a [Meaning]) -> List<'a> {
+    let mut items = Vec::new();
+    for meaning in meanings.iter() {
+        for (i, video) in meaning.videos.iter().enumerate() {
+            let mut lines = Vec::new();
+            if i == 0 {
+                lines.push(Spans::from(Span::styled(
+                    &meaning.title,
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                )));
+                if !meaning.definition.is_empty() {
+                    lines.push(Spans::from(Span::styled(
+                        &meaning.definition,
+                        Style::default().fg(Color::Red),
+                    )));
+                }
+            }
+            let mut video_line = vec![Span::raw(format!("  Video {}", i + 1))];
+            if let Some(variant) = &video.variant {
+                video_line.push(Span::styled(
+                    format!(" ({})", variant),
+                    Style::default().add_modifier(Modifier::DIM),
+                ));
+            }
+            lines.push(Spans::from(video_line));
+            items.push(ListItem::new(lines));
+        }
+    }
+
+    List::new(items)
+        .block(
+            Block::default()
+                .title(format!("Videos of {} (j/k, Enter, Esc)", word))
+                .borders(Borders::ALL),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">>")
+}
+
+// Video picker, or status of the video, alone on the screen
+// for the command line mode
+pub fn draw_video_player(
+    terminal: &mut Terminal<TermionBackend<termion::raw::RawTerminal<io::Stdout>>>,
+    video: &mut VideoPlayer,
+) {
+    let mut image_area = None;
+    terminal
+        .draw(|f| {
+            if let Some(picker) = video.picker.as_mut() {
+                let area = centered_rect(80, 80, f.size());
+                let list = video_picker_list(&picker.word, &picker.meanings);
+                f.render_stateful_widget(list, area, &mut picker.state);
+            } else {
+                let area = centered_rect(80, 80, f.size());
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(0), Constraint::Length(4)].as_ref())
+                    .split(area);
+                image_area = render_frame(f, chunks[0], video);
+                if let Some(text) = video_text(video) {
+                    let status = Paragraph::new(text)
+                        .block(Block::default().title("Video").borders(Borders::ALL))
+                        .wrap(Wrap { trim: true });
+                    f.render_widget(status, chunks[1]);
+                }
+            }
+        })
+        .unwrap();
+    write_video_image(terminal, video, image_area);
+}
+
+// Render the last frame of the video played in the terminal.
+// Return the area left blank when it is drawn with a graphics protocol.
+fn render_frame<B: Backend>(f: &mut Frame<B>, area: Rect, video: &VideoPlayer) -> Option<Rect> {
+    let frame = video.frame.as_ref()?;
+    let block = Block::default().borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    match video.graphics()? {
+        Graphics::HalfBlocks => {
+            f.render_widget(HalfBlocks::new(frame), inner);
+            None
+        }
+        _ => Some(inner),
+    }
+}
+
+// Render the information about a word with the status of its video,
+// and the video itself when it is played in the terminal.
+// Return the area of the frame left blank for a graphics protocol.
+fn render_information<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    para: Paragraph,
+    video: &VideoPlayer,
+) -> Option<Rect> {
+    let status = match video_text(video) {
+        Some(text) => Paragraph::new(text)
+            .block(Block::default().title("Video").borders(Borders::ALL))
+            .wrap(Wrap { trim: true }),
+        None => {
+            f.render_widget(para, area);
+            return None;
+        }
+    };
+
+    if video.frame.is_some() {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Percentage(30),
+                    Constraint::Min(0),
+                    Constraint::Length(4),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+        f.render_widget(para, chunks[0]);
+        f.render_widget(status, chunks[2]);
+        render_frame(f, chunks[1], video)
+    } else {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(4)].as_ref())
+            .split(area);
+        f.render_widget(para, chunks[0]);
+        f.render_widget(status, chunks[1]);
+        None
+    }
+}
+
+fn render_video_picker<B: Backend>(f: &mut Frame<B>, video: &mut VideoPlayer) {
+    if let Some(picker) = video.picker.as_mut() {
+        let area = centered_rect(60, 60, f.size());
+        let list = video_picker_list(&picker.word, &picker.meanings);
+        f.render_widget(Clear, area); //this clears out the background
+        f.render_stateful_widget(list, area, &mut picker.state);
+    }
+}
+
+// Draw the frame over the cells left blank by tui, for graphics protocols
+fn write_video_image(
+    terminal: &mut Terminal<TermionBackend<termion::raw::RawTerminal<io::Stdout>>>,
+    video: &VideoPlayer,
+    area: Option<Rect>,
+) {
+    if let (Some(area), Some(frame), Some(graphics)) = (area, &video.frame, video.graphics()) {
+        // The frame may be skipped, the next one will be drawn soon
+        write_image(terminal.backend_mut(), graphics, frame, area).unwrap_or_default();
+    }
+}
+
+// Create the tabs, with the tab `index` selected
+fn tabs(index: usize) -> Tabs<'static> {
+    let titles = vec![
+        Spans::from(vec![Span::styled(
+            "Dictionary",
+            Style::default().fg(Color::Yellow),
+        )]),
+        Spans::from(vec![Span::styled(
+            "Learn",
+            Style::default().fg(Color::Green),
+        )]),
+        Spans::from(vec![Span::styled(
+            "Statistics",
+            Style::default().fg(Color::Cyan),
+        )]),
+        Spans::from(vec![Span::styled(
+            "Alphabet",
+            Style::default().fg(Color::Magenta),
+        )]),
+    ];
+
+    Tabs::new(titles)
+        .block(Block::default().title("Mode").borders(Borders::ALL))
+        .style(Style::default().fg(Color::White))
+        .highlight_style(Style::default().add_modifier(Modifier::UNDERLINED))
+        .select(index)
+        .divider(DOT)
+}
+
 // Draw dictionary tab
 // Terminal type is ugly af :)
 pub fn draw_dictionary(
     terminal: &mut Terminal<TermionBackend<termion::raw::RawTerminal<io::Stdout>>>,
     states: &mut Selection,
     categories: &[Categorie],
+    title: &str,
+    video: &mut VideoPlayer,
+    favourites: &Favourites,
 ) {
+    let mut image_area = None;
     terminal
         .draw(|f| {
             // Create vertical chunks
@@ -84,16 +634,25 @@ pub fn draw_dictionary(
                 .split(vert_chunks[1]); // These chunks are in the second vertical chunk
 
             // Create list of categories
+            // The deck is only shown when categories come from several decks
+            let several_decks = categories.iter().any(|c| c.deck != categories[0].deck);
             let cat_items: Vec<ListItem> = categories
                 .iter()
                 .map(|i| {
-                    let lines = vec![Spans::from(Span::raw(&i.name))];
+                    let mut spans = vec![Span::raw(&i.name)];
+                    if several_decks {
+                        spans.push(Span::styled(
+                            format!(" [{}]", i.deck),
+                            Style::default().add_modifier(Modifier::DIM),
+                        ));
+                    }
+                    let lines = vec![Spans::from(spans)];
                     ListItem::new(lines).style(Style::default().fg(Color::Black).bg(Color::White))
                 })
                 .collect();
 
             let l_cat = List::new(cat_items)
-                .block(Block::default().title("Categories").borders(Borders::ALL))
+                .block(Block::default().title(title).borders(Borders::ALL))
                 .style(Style::default().fg(Color::White))
                 .highlight_style(Style::default().add_modifier(Modifier::ITALIC))
                 .highlight_symbol(">>");
@@ -103,7 +662,9 @@ pub fn draw_dictionary(
                 .words
                 .iter()
                 .map(|i| {
-                    let lines = vec![Spans::from(Span::raw(&i.name))];
+                    // Favourite words are starred
+                    let mark = if favourites.contains(i) { "* " } else { "" };
+                    let lines = vec![Spans::from(Span::raw(format!("{}{}", mark, i.name)))];
                     ListItem::new(lines).style(Style::default().fg(Color::Black).bg(Color::White))
                 })
                 .collect();
@@ -118,7 +679,8 @@ pub fn draw_dictionary(
             // - Word
             // - How to do it in LSF
             // - Link to video
-            let text = vec![
+            // - Parameters of the sign
+            let mut text = vec![
                 Spans::from(Span::styled(
                     categories[states.get_categorie_index()].words[states.get_word_index()]
                         .name
@@ -140,6 +702,9 @@ pub fn draw_dictionary(
                     Style::default().fg(Color::Blue),
                 )),
             ];
+            text.extend(parameters_text(
+                &categories[states.get_categorie_index()].words[states.get_word_index()],
+            ));
 
             let para = Paragraph::new(text)
                 .block(Block::default().title("Information").borders(Borders::ALL))
@@ -148,43 +713,223 @@ pub fn draw_dictionary(
                 .wrap(Wrap { trim: true });
 
             // Create the tabs
-            let titles = vec![
-                Spans::from(vec![Span::styled(
-                    "Dictionary",
-                    Style::default().fg(Color::Yellow),
-                )]),
-                Spans::from(vec![Span::styled(
-                    "Learn",
-                    Style::default().fg(Color::Green),
-                )]),
-            ];
-
-            let tabs = Tabs::new(titles)
-                .block(Block::default().title("Mode").borders(Borders::ALL))
-                .style(Style::default().fg(Color::White))
-                .highlight_style(Style::default().fg(Color::Yellow))
-                .select(0) // Select is hardcoded
-                .divider(DOT);
+            let tabs = tabs(0);
 
             // Render list of categories
             f.render_stateful_widget(l_cat, chunks[0], &mut states.categorie_state);
             // Render list of words
             f.render_stateful_widget(l_word, chunks[1], &mut states.word_state);
-            // Render information about the word
-            f.render_widget(para, chunks[2]);
+            // Render information about the word, and the video if any
+            image_area = render_information(f, chunks[2], para, video);
             // Render tabs
             f.render_widget(tabs, vert_chunks[0]);
+
+            // Render search pop-up
+            if let Some(search) = states.search.as_mut() {
+                let area = centered_rect(60, 60, f.size());
+                let popup_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+                    .split(area);
+
+                let prompt = Paragraph::new(Spans::from(vec![
+                    Span::styled("/", Style::default().fg(Color::Yellow)),
+                    Span::raw(&search.query),
+                ]))
+                .block(Block::default().title("Search").borders(Borders::ALL));
+
+                let hits: Vec<ListItem> = search
+                    .hits
+                    .iter()
+                    .map(|(cat, word)| {
+                        let word = &categories[*cat].words[*word];
+                        let lines = vec![
+                            Spans::from(vec![
+                                Span::styled(
+                                    &word.name,
+                                    Style::default().add_modifier(Modifier::BOLD),
+                                ),
+                                Span::styled(
+                                    format!(" [{}]", categories[*cat].name),
+                                    Style::default().add_modifier(Modifier::DIM),
+                                ),
+                            ]),
+                            Spans::from(Span::styled(
+                                &word.description,
+                                Style::default().fg(Color::Red),
+                            )),
+                        ];
+                        ListItem::new(lines)
+                    })
+                    .collect();
+                let hits_list = List::new(hits)
+                    .block(
+                        Block::default()
+                            .title(format!("{} words", search.hits.len()))
+                            .borders(Borders::ALL),
+                    )
+                    .highlight_style(Style::default().bg(Color::White).fg(Color::Black))
+                    .highlight_symbol(">>");
+
+                f.render_widget(Clear, area); //this clears out the background
+                f.render_widget(prompt, popup_chunks[0]);
+                f.render_stateful_widget(hits_list, popup_chunks[1], &mut search.state);
+            }
+
+            // Render video picker pop-up
+            render_video_picker(f, video);
         })
         .unwrap();
+    write_video_image(terminal, video, image_area);
+}
+
+// Score of a challenge and the best scores of its table,
+// displayed above the session summary
+fn challenge_text<'a>(session: &Session, high_scores: &HighScores) -> Vec<Spans<'a>> {
+    let title = Style::default().add_modifier(Modifier::BOLD);
+    let score = session.score();
+    let mut text = vec![Spans::from(Span::styled(
+        format!(
+            "Challenge {}: {}/{} in {}",
+            session.challenge,
+            score.correct,
+            score.words,
+            format_duration(&score.time)
+        ),
+        title,
+    ))];
+    if let Some(rank) = session.rank {
+        text.push(Spans::from(Span::styled(
+            format!("New high score, rank {}", rank + 1),
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+        )));
+    }
+
+    text.push(Spans::from(Span::raw("")));
+    text.push(Spans::from(Span::styled("High scores", title)));
+    for (i, score) in high_scores.get(&session.challenge).iter().enumerate() {
+        let style = if session.rank == Some(i) {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default()
+        };
+        let days = today().saturating_sub(score.day);
+        text.push(Spans::from(Span::styled(
+            format!(
+                "{}. {}/{} in {} ({})",
+                i + 1,
+                score.correct,
+                score.words,
+                format_duration(&score.time),
+                match days {
+                    0 => String::from("today"),
+                    1 => String::from("yesterday"),
+                    days => format!("{} days ago", days),
+                }
+            ),
+            style,
+        )));
+    }
+    text.push(Spans::from(Span::raw("")));
+    text
+}
+
+// Pop-up choosing the words of the next session, its size and order
+fn render_setup<B: Backend>(f: &mut Frame<B>, setup: &mut SessionSetup, categories: &[Categorie]) {
+    let value = |label: &str, value: String| {
+        ListItem::new(Spans::from(vec![
+            Span::styled(format!("{:<8}", label), Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!("< {} >", value)),
+        ]))
+    };
+    let mut items = vec![
+        value("Words", setup.source.label().to_string()),
+        value(
+            "Size",
+            match SIZES[setup.size] {
+                0 => String::from("every word"),
+                size => format!("{} words at most", size),
+            },
+        ),
+        value(
+            "Order",
+            String::from(if setup.shuffle { "shuffled" } else { "file order" }),
+        ),
+        value(
+            "Timer",
+            match TIMERS[setup.timer] {
+                0 => String::from("off"),
+                seconds => format!("challenge, {} seconds per word", seconds),
+            },
+        ),
+    ];
+    match setup.source {
+        WordSource::Categories => {
+            for (categorie, chosen) in categories.iter().zip(setup.chosen.iter()) {
+                let mark = if *chosen { "[x]" } else { "[ ]" };
+                items.push(ListItem::new(Spans::from(format!("{} {}", mark, categorie.name))));
+            }
+        }
+        WordSource::Numbers => {
+            items.push(value("From", BOUNDS[setup.smallest].to_string()));
+            items.push(value("To", BOUNDS[setup.largest].to_string()));
+        }
+        _ => {}
+    }
+
+    let area = centered_rect(60, 70, f.size());
+    let block = Block::default().title("New session").borders(Borders::ALL);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(2)].as_ref())
+        .split(block.inner(area));
+
+    let list = List::new(items)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">>");
+    let help = Paragraph::new(vec![
+        Spans::from(Span::styled(
+            setup.message.unwrap_or(""),
+            Style::default().fg(Color::Red),
+        )),
+        Spans::from(Span::styled(
+            "j/k move, h/l change, space choose, enter start",
+            Style::default().add_modifier(Modifier::DIM),
+        )),
+    ]);
+
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+    f.render_stateful_widget(list, chunks[0], &mut setup.state);
+    f.render_widget(help, chunks[1]);
 }
 
 pub fn draw_learn(
     terminal: &mut Terminal<TermionBackend<termion::raw::RawTerminal<io::Stdout>>>,
-    words_learn_set: &mut Vec<(&Word, WordState)>,
+    session: &mut Session,
     states: &mut Selection,
-    time: &Duration,
-    help: &mut bool,
+    video: &mut VideoPlayer,
+    high_scores: &HighScores,
 ) {
+    // Only the setup pop-up is drawn until the session starts
+    if session.configuring {
+        let categories = session.categories;
+        terminal
+            .draw(|f| {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+                    .split(f.size());
+                f.render_widget(tabs(1), chunks[0]);
+                render_setup(f, &mut session.setup, categories);
+            })
+            .unwrap();
+        return;
+    }
+
+    let words_learn_set = &session.words;
+    let time = session.time();
+    let mut image_area = None;
     terminal
         .draw(|f| {
             // Create vertical chunks
@@ -215,26 +960,47 @@ pub fn draw_learn(
                 .split(vert_chunks[1]); // These chunks are in the second vertical chunk
 
             let word_index = states.get_word_index();
-            // Display of the word
-            let mut text = vec![Spans::from(Span::styled(
-                words_learn_set[word_index].0.name.to_string(),
+            // The answer is typed or picked in the quiz modes
+            let answering = session.mode != LearnMode::Recall;
+            // The word is asked in the reverse direction
+            let hidden = session.review_direction() == CardDirection::Reverse;
+            let word = words_learn_set[word_index].0;
+            let name = Spans::from(Span::styled(
+                word.name.to_string(),
                 Style::default()
                     .fg(Color::Green)
                     .add_modifier(Modifier::BOLD),
-            ))];
+            ));
+            let description = if word.description.is_empty() {
+                Spans::from(Span::styled(
+                    "No description, press v to see the sign",
+                    Style::default().add_modifier(Modifier::DIM),
+                ))
+            } else {
+                Spans::from(Span::styled(
+                    word.description.to_string(),
+                    Style::default().fg(Color::Red),
+                ))
+            };
+            // The description is shown first in the reverse direction
+            let (prompt, answer) = if hidden {
+                (description, name)
+            } else {
+                (name, description)
+            };
+            // Display of the word, or the prompt of the typed answer
+            let mut text = if answering {
+                answer_text(session, word)
+            } else {
+                vec![prompt]
+            };
 
-            if *help {
-                let description_style = Style::default().fg(Color::Red);
+            if session.help && !answering {
                 let link_style = Style::default().fg(Color::Blue);
 
-                text.push(Spans::from(Span::styled(
-                    words_learn_set[word_index].0.description.to_string(),
-                    description_style,
-                )));
-                text.push(Spans::from(Span::styled(
-                    words_learn_set[word_index].0.link.to_string(),
-                    link_style,
-                )));
+                text.push(answer);
+                text.push(Spans::from(Span::styled(word.link.to_string(), link_style)));
+                text.extend(parameters_text(word));
             }
 
             let para = Paragraph::new(text)
@@ -248,7 +1014,7 @@ pub fn draw_learn(
             let millis = time.as_millis() / 100 % 10;
 
             // Display the index of the word
-            let text = vec![
+            let mut text = vec![
                 Spans::from(Span::styled(
                     format!("{}/{}", word_index + 1, words_learn_set.len()),
                     Style::default()
@@ -262,7 +1028,40 @@ pub fn draw_learn(
                         .fg(Color::Yellow)
                         .add_modifier(Modifier::ITALIC),
                 )),
+                Spans::from(Span::styled(
+                    match session.review_direction() {
+                        CardDirection::Forward => "word to sign",
+                        CardDirection::Reverse => "sign to word",
+                    },
+                    Style::default().add_modifier(Modifier::DIM),
+                )),
+                Spans::from(Span::raw("")),
             ];
+            // Display the countdown of a challenge, in red when it is almost over
+            if let Some(remaining) = session.remaining(states) {
+                let color = if remaining < Duration::from_secs(3) {
+                    Color::Red
+                } else {
+                    Color::Yellow
+                };
+                text.insert(
+                    3,
+                    Spans::from(Span::styled(
+                        format!("{} left", format_duration(&remaining)),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    )),
+                );
+            }
+            // Display the grading keys
+            for (key, grade) in GRADE_KEYS.iter() {
+                text.push(Spans::from(vec![
+                    Span::styled(
+                        format!("{} ", key),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(grade_label(*grade), grade_style(*grade)),
+                ]));
+            }
 
             let index_text = Paragraph::new(text)
                 .block(Block::default().title("Progression").borders(Borders::ALL))
@@ -271,37 +1070,24 @@ pub fn draw_learn(
                 .wrap(Wrap { trim: true });
 
             // Create the tabs
-            let titles = vec![
-                Spans::from(vec![Span::styled(
-                    "Dictionary",
-                    Style::default().fg(Color::Yellow),
-                )]),
-                Spans::from(vec![Span::styled(
-                    "Learn",
-                    Style::default().fg(Color::Green),
-                )]),
-            ];
+            let tabs = tabs(1);
 
-            let tabs = Tabs::new(titles)
-                .block(Block::default().title("Mode").borders(Borders::ALL))
-                .style(Style::default().fg(Color::White))
-                .highlight_style(Style::default().fg(Color::Yellow))
-                .select(0) // Select is hardcoded
-                .divider(DOT);
-
-            // Create progression bar
-            let progression = u16::try_from((word_index + 1) * 100).unwrap()
-                / u16::try_from(words_learn_set.len()).unwrap();
+            // Create progression bar, failed words are asked again in new rounds
+            let progression = ((word_index + 1) * 100 / words_learn_set.len().max(1)).min(100) as u16;
+            let gauge_title = match session.round() {
+                1 => String::from("Progression"),
+                round => format!("Round {} - failed words", round),
+            };
             let gauge = Gauge::default()
-                .block(Block::default().title("Progression").borders(Borders::ALL))
+                .block(Block::default().title(gauge_title).borders(Borders::ALL))
                 .gauge_style(Style::default().fg(Color::Yellow))
                 .style(Style::default().fg(Color::Yellow))
                 .percent(progression);
 
             // Render index
             f.render_widget(index_text, chunks[0]);
-            // Render information about the word
-            f.render_widget(para, chunks[2]);
+            // Render information about the word, and its video if any
+            image_area = render_information(f, chunks[2], para, video);
             // Render tabs
             f.render_widget(tabs, vert_chunks[0]);
             // Render progression bar
@@ -310,19 +1096,23 @@ pub fn draw_learn(
             let words: Vec<ListItem> = words_learn_set
                 .iter()
                 .map(|(word, status)| {
+                    // The current word is the answer in the reverse direction
+                    let name = match status {
+                        WordState::Current | WordState::Peeked if hidden => "???",
+                        _ => word.name.as_str(),
+                    };
                     let s = match status {
-                        WordState::Failed => Style::default()
-                            .bg(Color::Red)
-                            .add_modifier(Modifier::CROSSED_OUT),
-                        WordState::Valided => Style::default()
-                            .bg(Color::Green)
-                            .add_modifier(Modifier::CROSSED_OUT),
-                        WordState::Current => Style::default()
+                        WordState::Graded(grade, ..) => {
+                            grade_style(*grade).add_modifier(Modifier::CROSSED_OUT)
+                        }
+                        WordState::Skipped(_) => Style::default()
+                            .add_modifier(Modifier::DIM | Modifier::CROSSED_OUT),
+                        WordState::Current | WordState::Peeked => Style::default()
                             .fg(Color::Gray)
                             .add_modifier(Modifier::BOLD),
                         _ => Style::default().add_modifier(Modifier::HIDDEN),
                     };
-                    let lines = vec![Spans::from(Span::styled(word.name.to_string(), s))];
+                    let lines = vec![Spans::from(Span::styled(name, s))];
                     ListItem::new(lines)
                 })
                 .collect();
@@ -336,13 +1126,296 @@ pub fn draw_learn(
             // Render final pop-up
             if states.is_done() {
                 let size = f.size();
-                let text = vec![Spans::from(Span::raw("pouet pouet"))];
+                let mut text = summary_text(session.results(), time, session.round());
+                if session.limit.is_some() {
+                    text.splice(0..0, challenge_text(session, high_scores));
+                }
                 let paragraph = Paragraph::new(text)
-                    .block(Block::default().title("Done").borders(Borders::ALL));
-                let area = centered_rect(60, 20, size);
+                    .block(Block::default().title("Done").borders(Borders::ALL))
+                    .alignment(Alignment::Center)
+                    .wrap(Wrap { trim: true });
+                let area = centered_rect(60, 60, size);
                 f.render_widget(Clear, area); //this clears out the background
                 f.render_widget(paragraph, area);
             }
+
+            // Render pause pop-up, hiding the word
+            if session.is_paused() {
+                let paragraph = Paragraph::new(vec![
+                    Spans::from(Span::styled(
+                        format!("{} seconds", time.as_secs()),
+                        Style::default().fg(Color::Yellow),
+                    )),
+                    Spans::from(Span::raw("")),
+                    Spans::from(Span::styled(
+                        "p or ctrl-s to resume",
+                        Style::default().add_modifier(Modifier::DIM),
+                    )),
+                ])
+                .block(Block::default().title("Paused").borders(Borders::ALL))
+                .alignment(Alignment::Center);
+                let area = centered_rect(60, 60, f.size());
+                f.render_widget(Clear, area);
+                f.render_widget(paragraph, area);
+            }
+
+            // Render video picker pop-up
+            render_video_picker(f, video);
+        })
+        .unwrap();
+    write_video_image(terminal, video, image_area);
+}
+
+pub fn draw_statistics(
+    terminal: &mut Terminal<TermionBackend<termion::raw::RawTerminal<io::Stdout>>>,
+    stats: &Statistics,
+) {
+    terminal
+        .draw(|f| {
+            // Create vertical chunks
+            let vert_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Length(3),
+                        Constraint::Length(6),
+                        Constraint::Percentage(50),
+                        Constraint::Min(0),
+                    ]
+                    .as_ref(),
+                )
+                .split(f.size());
+
+            // Create horizontal chunks for the bottom part
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+                .split(vert_chunks[3]);
+
+            // Reviews per day
+            let sparkline = Sparkline::default()
+                .block(
+                    Block::default()
+                        .title(format!(
+                            "Reviews per day ({} days) - {} reviews in total - {} per word",
+                            HISTORY_DAYS,
+                            stats.total_reviews,
+                            format_duration(&stats.average_spent)
+                        ))
+                        .borders(Borders::ALL),
+                )
+                .style(Style::default().fg(Color::Yellow))
+                .data(&stats.reviews_per_day);
+
+            // Retention rate per day
+            let last_day = (HISTORY_DAYS - 1) as f64;
+            let datasets = vec![Dataset::default()
+                .name("Retention")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Green))
+                .data(&stats.retention_per_day)];
+            let chart = Chart::new(datasets)
+                .block(
+                    Block::default()
+                        .title(format!("Retention rate - {:.0}% overall", stats.retention))
+                        .borders(Borders::ALL),
+                )
+                .x_axis(
+                    Axis::default()
+                        .title("Days")
+                        .bounds([0.0, last_day])
+                        .labels(vec![
+                            Span::raw(format!("-{}", HISTORY_DAYS - 1)),
+                            Span::raw("today"),
+                        ]),
+                )
+                .y_axis(
+                    Axis::default()
+                        .title("%")
+                        .bounds([0.0, 100.0])
+                        .labels(vec![Span::raw("0"), Span::raw("50"), Span::raw("100")]),
+                );
+
+            // Mastery of each categorie
+            let mastery: Vec<(&str, u64)> = stats
+                .mastery
+                .iter()
+                .map(|(name, percent)| (name.as_str(), *percent))
+                .collect();
+            let barchart = BarChart::default()
+                .block(
+                    Block::default()
+                        .title("Mastered words per categorie (%)")
+                        .borders(Borders::ALL),
+                )
+                .data(&mastery)
+                .bar_width(9)
+                .max(100)
+                .bar_style(Style::default().fg(Color::Green))
+                .value_style(Style::default().fg(Color::Black).bg(Color::Green));
+
+            // Hardest words
+            let hardest: Vec<ListItem> = stats
+                .hardest
+                .iter()
+                .map(|(deck, word, failures, reviews)| {
+                    let lines = vec![Spans::from(vec![
+                        Span::styled(word.to_string(), Style::default().fg(Color::Red)),
+                        Span::styled(format!(" ({})", deck), Style::default().fg(Color::DarkGray)),
+                        Span::raw(format!(" {}/{} failed", failures, reviews)),
+                    ])];
+                    ListItem::new(lines)
+                })
+                .collect();
+            let hardest_list = List::new(hardest)
+                .block(Block::default().title("Hardest words").borders(Borders::ALL));
+
+            // Render tabs
+            f.render_widget(tabs(2), vert_chunks[0]);
+            f.render_widget(sparkline, vert_chunks[1]);
+            f.render_widget(chart, vert_chunks[2]);
+            f.render_widget(barchart, chunks[0]);
+            f.render_widget(hardest_list, chunks[1]);
+        })
+        .unwrap();
+}
+
+pub fn draw_alphabet(
+    terminal: &mut Terminal<TermionBackend<termion::raw::RawTerminal<io::Stdout>>>,
+    spelling: &Spelling,
+) {
+    terminal
+        .draw(|f| {
+            // Create vertical chunks
+            let vert_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Length(3),
+                        Constraint::Length(6),
+                        Constraint::Min(0),
+                        Constraint::Length(3),
+                    ]
+                    .as_ref(),
+                )
+                .split(f.size());
+
+            // Create horizontal chunks for the handshapes
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+                .split(vert_chunks[2]);
+
+            // The word, then its letters: the current one is highlighted
+            // and the next ones are dimmed
+            let current = spelling.shown.checked_sub(1);
+            let letters: Vec<Span> = spelling
+                .letters
+                .iter()
+                .enumerate()
+                .map(|(i, letter)| {
+                    let style = if Some(i) == current {
+                        Style::default()
+                            .fg(Color::Green)
+                            .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+                    } else if i < spelling.shown {
+                        Style::default().fg(Color::Green)
+                    } else {
+                        Style::default().add_modifier(Modifier::DIM)
+                    };
+                    Span::styled(format!(" {} ", letter.to_uppercase()), style)
+                })
+                .collect();
+            let prompt = if spelling.quiz && spelling.shown == 0 {
+                "Spell the word, then show its letters with l to check"
+            } else {
+                ""
+            };
+            let text = vec![
+                Spans::from(Span::styled(
+                    spelling.text.to_string(),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Spans::from(Span::raw("")),
+                Spans::from(letters),
+                Spans::from(Span::styled(prompt, Style::default().add_modifier(Modifier::DIM))),
+            ];
+            let title = match spelling.source {
+                SpellingSource::Words => "Word",
+                SpellingSource::Names => "Name",
+            };
+            let word = Paragraph::new(text)
+                .block(Block::default().title(title).borders(Borders::ALL))
+                .alignment(Alignment::Center);
+
+            // Handshapes of the letters shown so far
+            let shown: Vec<ListItem> = spelling
+                .letters
+                .iter()
+                .take(spelling.shown)
+                .map(|letter| {
+                    let description = spelling
+                        .handshape(*letter)
+                        .map(|word| word.description.as_str())
+                        .unwrap_or("No handshape in the alphabet");
+                    ListItem::new(Spans::from(vec![
+                        Span::styled(
+                            format!("{} ", letter.to_uppercase()),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(description),
+                    ]))
+                })
+                .collect();
+            let shown_list = List::new(shown)
+                .block(Block::default().title("Letters").borders(Borders::ALL));
+
+            // Handshape of the current letter
+            let mut text = vec![];
+            if let Some(letter) = current.map(|i| spelling.letters[i]) {
+                text.push(Spans::from(Span::styled(
+                    letter.to_uppercase().to_string(),
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                )));
+                if let Some(word) = spelling.handshape(letter) {
+                    text.push(Spans::from(Span::styled(
+                        word.description.to_string(),
+                        Style::default().fg(Color::Red),
+                    )));
+                    text.extend(parameters_text(word));
+                }
+            }
+            let handshape = Paragraph::new(text)
+                .block(Block::default().title("Handshape").borders(Borders::ALL))
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+
+            let help = Paragraph::new(Spans::from(Span::styled(
+                format!(
+                    "h/l letters, space {}, r restart, n next, w {}, s {}, +/- speed ({:.1} s)",
+                    if spelling.playing { "stop" } else { "play" },
+                    match spelling.source {
+                        SpellingSource::Words => "names",
+                        SpellingSource::Names => "words",
+                    },
+                    if spelling.quiz { "read" } else { "spelling quiz" },
+                    SPEEDS[spelling.speed].as_secs_f32()
+                ),
+                Style::default().add_modifier(Modifier::DIM),
+            )))
+            .block(Block::default().borders(Borders::ALL))
+            .alignment(Alignment::Center);
+
+            f.render_widget(tabs(3), vert_chunks[0]);
+            f.render_widget(word, vert_chunks[1]);
+            f.render_widget(shown_list, chunks[0]);
+            f.render_widget(handshape, chunks[1]);
+            f.render_widget(help, vert_chunks[3]);
         })
         .unwrap();
 }
@@ -0,0 +1,192 @@
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+
+use crate::video_source::{Meaning, Video};
+
+// Only the video tags are known from the pages of Elix, the selectors of
+// the meanings, titles, definitions and variants have not been checked
+// against a saved page yet. When they match nothing, the videos of the
+// page are kept as a single meaning.
+
+// Blocks of the page describing one meaning of the word
+const MEANING: &str = ".signification, .meaning, .sens";
+// Title of a meaning, usually the word with its grammatical category
+const TITLE: &str = "h2, h3, h4, .title, .titre";
+// French definition of a meaning
+const DEFINITION: &str = ".definition, .def";
+// Videos of the signs, Elix links them in the tag
+// (`<video src="https://www.elix-lsf.fr/...mp4"></video>`)
+const VIDEO: &str = "video[src]";
+// Name of a regional or alternative variant of the sign, next to the video
+const VARIANT: &str = ".variante, .variant, figcaption";
+
+// Text of an element with its whitespace collapsed
+fn text(element: ElementRef) -> String {
+    element
+        .text()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn first_text(element: ElementRef, selector: &Selector) -> String {
+    element.select(selector).next().map(text).unwrap_or_default()
+}
+
+// Videos found in an element, links are relative to the page
+fn videos(element: ElementRef, page: &Url) -> Vec<Video> {
+    let video = Selector::parse(VIDEO).unwrap();
+    let variant = Selector::parse(VARIANT).unwrap();
+
+    element
+        .select(&video)
+        .filter_map(|tag| {
+            let uri = page.join(tag.value().attr("src")?).ok()?;
+            // The variant is described next to the video, in its largest
+            // container holding no other video
+            let mut container = None;
+            for ancestor in tag.ancestors().filter_map(ElementRef::wrap) {
+                if ancestor.select(&video).nth(1).is_some() {
+                    break;
+                }
+                container = Some(ancestor);
+                if ancestor.id() == element.id() {
+                    break;
+                }
+            }
+            let variant = container
+                .map(|container| first_text(container, &variant))
+                .filter(|v| !v.is_empty());
+            Some(Video {
+                uri: String::from(uri),
+                variant,
+            })
+        })
+        .collect()
+}
+
+// Meanings of a dictionary page, each with its definition and the videos
+// of its signs. A page without meaning blocks gives a single meaning.
+pub fn parse_page(word: &str, page: &Url, body: &str) -> Vec<Meaning> {
+    let document = Html::parse_document(body);
+    let root = document.root_element();
+    let meaning = Selector::parse(MEANING).unwrap();
+    let title = Selector::parse(TITLE).unwrap();
+    let definition = Selector::parse(DEFINITION).unwrap();
+
+    let mut meanings: Vec<Meaning> = root
+        .select(&meaning)
+        .map(|block| Meaning {
+            title: Some(first_text(block, &title))
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| word.to_string()),
+            definition: first_text(block, &definition),
+            videos: videos(block, page),
+        })
+        .filter(|meaning| !meaning.videos.is_empty())
+        .collect();
+
+    if meanings.is_empty() {
+        let videos = videos(root, page);
+        if !videos.is_empty() {
+            meanings.push(Meaning {
+                title: word.to_string(),
+                definition: first_text(root, &definition),
+                videos,
+            });
+        }
+    }
+
+    meanings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page() -> Url {
+        Url::parse("https://dico.elix-lsf.fr/dictionnaire/bonjour").unwrap()
+    }
+
+    fn uris(meaning: &Meaning) -> Vec<&str> {
+        meaning.videos.iter().map(|video| video.uri.as_str()).collect()
+    }
+
+    #[test]
+    fn videos_of_the_saved_page_are_found() {
+        let body = include_str!("../fixtures/elix_bonjour.html");
+        let meanings = parse_page("bonjour", &page(), body);
+        assert_eq!(meanings.len(), 1);
+        assert_eq!(meanings[0].title, "bonjour");
+        assert_eq!(
+            uris(&meanings[0]),
+            [
+                "https://www.elix-lsf.fr/IMG/mp4/bonjour.mp4",
+                "https://www.elix-lsf.fr/IMG/mp4/bonjour-2.mp4",
+            ]
+        );
+        assert!(meanings[0].videos.iter().all(|video| video.variant.is_none()));
+    }
+
+    #[test]
+    fn videos_of_a_page_without_meanings_make_one_meaning() {
+        let body = r#"<html><body>
+<h1>bonjour</h1>
+<p class="definition">Formule de salutation</p>
+<video src="https://www.elix-lsf.fr/IMG/mp4/bonjour.mp4"></video>
+<video src="/IMG/mp4/bonjour-2.mp4"></video>
+</body></html>"#;
+        let meanings = parse_page("bonjour", &page(), body);
+        assert_eq!(meanings.len(), 1);
+        assert_eq!(meanings[0].title, "bonjour");
+        assert_eq!(meanings[0].definition, "Formule de salutation");
+        assert_eq!(
+            uris(&meanings[0]),
+            [
+                "https://www.elix-lsf.fr/IMG/mp4/bonjour.mp4",
+                "https://dico.elix-lsf.fr/IMG/mp4/bonjour-2.mp4",
+            ]
+        );
+    }
+
+    #[test]
+    fn each_video_has_its_own_variant() {
+        let body = r#"<div class="signification">
+  <h3>Voler (oiseau)</h3>
+  <p class="definition">Se déplacer dans l'air</p>
+  <figure><video src="/a.mp4"></video><figcaption>Paris</figcaption></figure>
+  <figure><video src="/b.mp4"></video><figcaption>Toulouse</figcaption></figure>
+  <video src="/c.mp4"></video>
+</div>
+<div class="signification">
+  <h3>Voler (dérober)</h3>
+  <video src="/d.mp4"></video>
+</div>"#;
+        let meanings = parse_page("voler", &page(), body);
+        assert_eq!(meanings.len(), 2);
+        assert_eq!(meanings[0].title, "Voler (oiseau)");
+        assert_eq!(meanings[0].definition, "Se déplacer dans l'air");
+        let variants: Vec<Option<&str>> = meanings[0]
+            .videos
+            .iter()
+            .map(|video| video.variant.as_deref())
+            .collect();
+        assert_eq!(variants, [Some("Paris"), Some("Toulouse"), None]);
+        assert_eq!(meanings[1].title, "Voler (dérober)");
+        assert_eq!(uris(&meanings[1]), ["https://dico.elix-lsf.fr/d.mp4"]);
+    }
+
+    #[test]
+    fn a_video_is_counted_once() {
+        let body = r#"<video src="/a.mp4"><source src="/a.mp4" type="video/mp4"></video>"#;
+        let meanings = parse_page("a", &page(), body);
+        assert_eq!(uris(&meanings[0]), ["https://dico.elix-lsf.fr/a.mp4"]);
+    }
+
+    #[test]
+    fn a_page_without_videos_has_no_meaning() {
+        assert!(parse_page("inconnu", &page(), "<p>Aucun résultat</p>").is_empty());
+    }
+}
@@ -6,9 +6,14 @@ use std::time::Duration;
 use termion::event::Key;
 use termion::input::TermRead;
 
+use crate::search_video::VideoEvent;
+
 pub enum Event<I> {
     Input(I),
     Tick,
+    // Progress of a video lookup running in another thread,
+    // with the number of the lookup
+    Video(u64, VideoEvent),
 }
 
 pub struct Events {
@@ -37,13 +42,9 @@ impl Events {
             let tx = tx.clone();
             thread::spawn(move || {
                 let stdin = io::stdin();
-                for evt in stdin.keys() {
-                    if let Ok(key) = evt {
-                        tx.send(Event::Input(key)).unwrap_or_default();
-                        if key == Key::Char('q') {
-                            return;
-                        };
-                    };
+                // Keep reading after 'q', it may be typed in a prompt
+                for key in stdin.keys().flatten() {
+                    tx.send(Event::Input(key)).unwrap_or_default();
                 }
             })
         };
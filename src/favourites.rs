@@ -0,0 +1,72 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::PathBuf;
+
+use yaml_rust::yaml::Hash;
+use yaml_rust::Yaml;
+
+use crate::loader::Word;
+use crate::storage::{data_dir, load_yaml, save_yaml};
+
+// Words marked in tab 'Dictionary' to be learned together,
+// stored in the data directory
+pub struct Favourites {
+    path: PathBuf,
+    // Names of the words of each deck, sorted to keep
+    // the file readable and diffable
+    decks: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Favourites {
+    pub fn load() -> Result<Self, io::Error> {
+        let path = data_dir().join("favourites.yaml");
+        let mut decks: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        if let Some(doc) = load_yaml(&path)? {
+            if let Some(nodes) = doc["decks"].as_hash() {
+                for (deck, words) in nodes {
+                    if let (Some(deck), Some(words)) = (deck.as_str(), words.as_vec()) {
+                        decks
+                            .entry(deck.to_string())
+                            .or_default()
+                            .extend(words.iter().filter_map(|word| word.as_str().map(String::from)));
+                    }
+                }
+            }
+        }
+
+        Ok(Self { path, decks })
+    }
+
+    fn save(&self) -> Result<(), io::Error> {
+        let mut decks = Hash::new();
+        for (deck, words) in self.decks.iter() {
+            let words = words.iter().map(|word| Yaml::String(word.to_string())).collect();
+            decks.insert(Yaml::String(deck.to_string()), Yaml::Array(words));
+        }
+        let mut doc = Hash::new();
+        doc.insert(Yaml::from_str("decks"), Yaml::Hash(decks));
+        save_yaml(&self.path, &Yaml::Hash(doc))
+    }
+
+    pub fn contains(&self, word: &Word) -> bool {
+        self.decks
+            .get(&word.deck)
+            .is_some_and(|words| words.contains(&word.name))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.decks.is_empty()
+    }
+
+    // Add the word, or remove it if it is already a favourite, and save
+    pub fn toggle(&mut self, word: &Word) -> Result<(), io::Error> {
+        let words = self.decks.entry(word.deck.to_string()).or_default();
+        if !words.remove(&word.name) {
+            words.insert(word.name.to_string());
+        }
+        if words.is_empty() {
+            self.decks.remove(&word.deck);
+        }
+        self.save()
+    }
+}
@@ -0,0 +1,141 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use yaml_rust::yaml::Hash;
+use yaml_rust::Yaml;
+
+use crate::storage::{data_dir, load_yaml, save_yaml};
+
+// Number of scores kept for each challenge
+pub const HIGH_SCORES: usize = 5;
+
+// Result of a timed challenge
+#[derive(Clone)]
+pub struct Score {
+    // Words answered right before the end of their countdown
+    pub correct: usize,
+    pub words: usize,
+    // Time of the whole challenge
+    pub time: Duration,
+    // Day (since epoch) of the challenge
+    pub day: u64,
+}
+
+impl Score {
+    // Better than another score of the same table: a higher share
+    // of right words, then a faster time
+    fn beats(&self, other: &Score) -> bool {
+        let share = (self.correct * other.words.max(1)).cmp(&(other.correct * self.words.max(1)));
+        share.then(other.time.cmp(&self.time)) == Ordering::Greater
+    }
+
+    fn to_yaml(&self) -> Yaml {
+        let mut h = Hash::new();
+        h.insert(Yaml::from_str("correct"), Yaml::Integer(self.correct as i64));
+        h.insert(Yaml::from_str("words"), Yaml::Integer(self.words as i64));
+        h.insert(
+            Yaml::from_str("time"),
+            Yaml::Integer(self.time.as_millis() as i64),
+        );
+        h.insert(Yaml::from_str("day"), Yaml::Integer(self.day as i64));
+        Yaml::Hash(h)
+    }
+
+    fn from_yaml(node: &Yaml) -> Option<Self> {
+        Some(Self {
+            correct: node["correct"].as_i64()? as usize,
+            words: node["words"].as_i64()? as usize,
+            time: Duration::from_millis(node["time"].as_i64()? as u64),
+            day: node["day"].as_i64()? as u64,
+        })
+    }
+}
+
+// Best scores of the timed challenges of each categorie,
+// stored in the data directory
+pub struct HighScores {
+    path: PathBuf,
+    // Best first, the highest share of right words then the fastest
+    tables: BTreeMap<String, Vec<Score>>,
+}
+
+impl HighScores {
+    pub fn load() -> Result<Self, io::Error> {
+        let path = data_dir().join("high_scores.yaml");
+        let mut tables = BTreeMap::new();
+
+        if let Some(doc) = load_yaml(&path)? {
+            if let Some(challenges) = doc["challenges"].as_hash() {
+                for (name, scores) in challenges {
+                    if let (Some(name), Some(scores)) = (name.as_str(), scores.as_vec()) {
+                        let scores = scores.iter().filter_map(Score::from_yaml).collect();
+                        tables.insert(name.to_string(), scores);
+                    }
+                }
+            }
+        }
+
+        Ok(Self { path, tables })
+    }
+
+    fn save(&self) -> Result<(), io::Error> {
+        let mut challenges = Hash::new();
+        for (name, scores) in self.tables.iter() {
+            challenges.insert(
+                Yaml::String(name.to_string()),
+                Yaml::Array(scores.iter().map(Score::to_yaml).collect()),
+            );
+        }
+        let mut doc = Hash::new();
+        doc.insert(Yaml::from_str("challenges"), Yaml::Hash(challenges));
+        save_yaml(&self.path, &Yaml::Hash(doc))
+    }
+
+    pub fn get(&self, name: &str) -> &[Score] {
+        self.tables.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // Add a score to the table of a challenge and save it.
+    // Return its rank from 0, if it is one of the best scores.
+    pub fn add(&mut self, name: &str, score: Score) -> Result<Option<usize>, io::Error> {
+        let scores = self.tables.entry(name.to_string()).or_default();
+        let rank = scores
+            .iter()
+            .position(|s| score.beats(s))
+            .unwrap_or(scores.len());
+        if rank >= HIGH_SCORES {
+            return Ok(None);
+        }
+        scores.insert(rank, score);
+        scores.truncate(HIGH_SCORES);
+        self.save()?;
+        Ok(Some(rank))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(correct: usize, words: usize, seconds: u64) -> Score {
+        Score {
+            correct,
+            words,
+            time: Duration::from_secs(seconds),
+            day: 0,
+        }
+    }
+
+    #[test]
+    fn scores_rank_by_share_of_right_words_then_time() {
+        // 9 out of 10 beats 10 out of 20, whatever the number of words
+        assert!(score(9, 10, 60).beats(&score(10, 20, 30)));
+        assert!(!score(10, 20, 30).beats(&score(9, 10, 60)));
+        // Same share, the fastest wins
+        assert!(score(5, 10, 30).beats(&score(10, 20, 40)));
+        assert!(!score(5, 10, 30).beats(&score(5, 10, 30)));
+    }
+}
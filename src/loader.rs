@@ -1,6 +1,15 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs;
 use std::fs::File;
+use std::io;
 use std::io::prelude::*;
-use yaml_rust::YamlLoader;
+use std::path::Path;
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::Marker;
+use yaml_rust::{Yaml, YamlLoader};
 
 /*
  * Categorie and Word structure are clonable because
@@ -12,44 +21,704 @@ use yaml_rust::YamlLoader;
 #[derive(Clone)]
 pub struct Categorie {
     pub name: String,
+    // Name of the file the categorie comes from
+    pub deck: String,
     pub words: Vec<Word>,
 }
 
 #[derive(Clone)]
 pub struct Word {
     pub name: String,
+    // Name of the file the word comes from, words of different
+    // decks may share a name and are learned apart
+    pub deck: String,
     pub description: String,
     pub link: String,
+    // Other words accepted as answers
+    pub synonyms: Vec<String>,
+    pub parameters: Parameters,
 }
 
-// Load yaml entries into a vector of categories
-pub fn load_file(file: &str) -> Vec<Categorie> {
-    let mut file = File::open(file).expect("Unable to open file");
+// Parameters of a sign in LSF
+#[derive(Clone, Copy, PartialEq)]
+pub enum Parameter {
+    // Handshape
+    Configuration,
+    Emplacement,
+    Mouvement,
+    Orientation,
+    // Facial expression
+    Expression,
+}
+
+impl Parameter {
+    pub const ALL: [Parameter; 5] = [
+        Parameter::Configuration,
+        Parameter::Emplacement,
+        Parameter::Mouvement,
+        Parameter::Orientation,
+        Parameter::Expression,
+    ];
+
+    // Key of the parameter in the yaml file
+    pub fn key(self) -> &'static str {
+        match self {
+            Parameter::Configuration => "configuration",
+            Parameter::Emplacement => "emplacement",
+            Parameter::Mouvement => "mouvement",
+            Parameter::Orientation => "orientation",
+            Parameter::Expression => "expression",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Parameter::Configuration => "Configuration",
+            Parameter::Emplacement => "Emplacement",
+            Parameter::Mouvement => "Mouvement",
+            Parameter::Orientation => "Orientation",
+            Parameter::Expression => "Expression",
+        }
+    }
+}
+
+// Optional structured description of a sign
+#[derive(Clone, Default)]
+pub struct Parameters {
+    pub configuration: Option<String>,
+    pub emplacement: Option<String>,
+    pub mouvement: Option<String>,
+    pub orientation: Option<String>,
+    pub expression: Option<String>,
+}
+
+impl Parameters {
+    pub fn get(&self, parameter: Parameter) -> Option<&str> {
+        let value = match parameter {
+            Parameter::Configuration => &self.configuration,
+            Parameter::Emplacement => &self.emplacement,
+            Parameter::Mouvement => &self.mouvement,
+            Parameter::Orientation => &self.orientation,
+            Parameter::Expression => &self.expression,
+        };
+        value.as_deref()
+    }
+
+    fn get_mut(&mut self, parameter: Parameter) -> &mut Option<String> {
+        match parameter {
+            Parameter::Configuration => &mut self.configuration,
+            Parameter::Emplacement => &mut self.emplacement,
+            Parameter::Mouvement => &mut self.mouvement,
+            Parameter::Orientation => &mut self.orientation,
+            Parameter::Expression => &mut self.expression,
+        }
+    }
+}
+
+// What to do when an entry of the file is invalid
+#[derive(Clone, Copy, PartialEq)]
+pub enum LoadPolicy {
+    // The whole file is rejected on the first invalid entry
+    Strict,
+    // Invalid entries are skipped and reported as warnings
+    Lenient,
+}
+
+#[derive(Debug)]
+pub enum LoadErrorKind {
+    Io(io::Error),
+    Syntax(String),
+    MissingField(&'static str),
+    WrongType(&'static str, &'static str),
+    // Categorie without any valid word
+    NoWord,
+    // Deck named after the same file stem as an earlier file
+    DuplicateDeck(String),
+}
+
+// Error raised while loading a words file
+#[derive(Debug)]
+pub struct LoadError {
+    pub file: String,
+    // Position in the file, lines and columns start from 1
+    pub line: usize,
+    pub col: usize,
+    pub categorie: Option<usize>,
+    pub word: Option<usize>,
+    pub kind: LoadErrorKind,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.file)?;
+        if self.line > 0 {
+            write!(f, ":{}:{}", self.line, self.col)?;
+        }
+        write!(f, ": ")?;
+        if let Some(cat) = self.categorie {
+            write!(f, "categorie #{}", cat + 1)?;
+            if let Some(word) = self.word {
+                write!(f, ", word #{}", word + 1)?;
+            }
+            write!(f, ": ")?;
+        }
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl fmt::Display for LoadErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadErrorKind::Io(e) => write!(f, "unable to read file ({})", e),
+            LoadErrorKind::Syntax(info) => write!(f, "invalid YAML ({})", info),
+            LoadErrorKind::MissingField(field) => write!(f, "missing field '{}'", field),
+            LoadErrorKind::WrongType(field, expected) => {
+                write!(f, "field '{}' should be {}", field, expected)
+            }
+            LoadErrorKind::NoWord => write!(f, "no valid word"),
+            LoadErrorKind::DuplicateDeck(file) => {
+                write!(f, "deck name already used by {}, rename one of the files", file)
+            }
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            LoadErrorKind::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+// Collect the position of every mapping of the first document.
+// A block mapping is marked at the colon of its first key,
+// so the position of that key is kept instead.
+struct MappingMarks {
+    marks: Vec<Marker>,
+    documents: usize,
+    // The next event is the first key of a mapping
+    first_key: bool,
+}
+
+impl MarkedEventReceiver for MappingMarks {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        if self.first_key {
+            self.marks.push(mark);
+            self.first_key = false;
+        }
+        match ev {
+            Event::DocumentStart => self.documents += 1,
+            Event::MappingStart(_) if self.documents == 1 => self.first_key = true,
+            _ => {}
+        }
+    }
+}
+
+// `YamlLoader` drops positions, so the file is parsed a second time
+// and mappings are matched with the loaded tree in document order.
+struct Context<'a> {
+    file: &'a str,
+    marks: HashMap<*const Yaml, Marker>,
+}
+
+impl<'a> Context<'a> {
+    fn deck(&self) -> String {
+        deck_name(self.file)
+    }
+
+    fn index_marks(&mut self, node: &Yaml, marks: &[Marker], next: &mut usize) {
+        match node {
+            Yaml::Hash(h) => {
+                if let Some(mark) = marks.get(*next) {
+                    self.marks.insert(node, *mark);
+                }
+                *next += 1;
+                for (k, v) in h {
+                    self.index_marks(k, marks, next);
+                    self.index_marks(v, marks, next);
+                }
+            }
+            Yaml::Array(v) => v.iter().for_each(|n| self.index_marks(n, marks, next)),
+            _ => {}
+        }
+    }
+
+    fn error(
+        &self,
+        node: &Yaml,
+        categorie: Option<usize>,
+        word: Option<usize>,
+        kind: LoadErrorKind,
+    ) -> LoadError {
+        let (line, col) = self
+            .marks
+            .get(&(node as *const Yaml))
+            .map(|m| (m.line(), m.col() + 1))
+            .unwrap_or((0, 0));
+        LoadError {
+            file: self.file.to_string(),
+            line,
+            col,
+            categorie,
+            word,
+            kind,
+        }
+    }
+}
+
+// Read a string field of a mapping
+fn field<'y>(node: &'y Yaml, name: &'static str) -> Result<&'y str, LoadErrorKind> {
+    match &node[name] {
+        Yaml::BadValue => Err(LoadErrorKind::MissingField(name)),
+        Yaml::String(s) => Ok(s),
+        _ => Err(LoadErrorKind::WrongType(name, "a string")),
+    }
+}
+
+// Read an optional string field of a mapping
+fn optional_field<'y>(
+    node: &'y Yaml,
+    name: &'static str,
+) -> Result<Option<&'y str>, LoadErrorKind> {
+    match &node[name] {
+        Yaml::BadValue => Ok(None),
+        _ => field(node, name).map(Some),
+    }
+}
+
+// Read an optional list of strings of a mapping
+fn optional_list(node: &Yaml, name: &'static str) -> Result<Vec<String>, LoadErrorKind> {
+    match &node[name] {
+        Yaml::BadValue => Ok(Vec::new()),
+        Yaml::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Yaml::String(s) => Ok(s.to_string()),
+                _ => Err(LoadErrorKind::WrongType(name, "a list of strings")),
+            })
+            .collect(),
+        _ => Err(LoadErrorKind::WrongType(name, "a list of strings")),
+    }
+}
+
+fn load_word(node: &Yaml, deck: &str) -> Result<Word, LoadErrorKind> {
+    if node.as_hash().is_none() {
+        return Err(LoadErrorKind::WrongType("mot", "a mapping"));
+    }
+    let mut parameters = Parameters::default();
+    for parameter in Parameter::ALL.iter() {
+        *parameters.get_mut(*parameter) = optional_field(node, parameter.key())?.map(String::from);
+    }
+    Ok(Word {
+        name: field(node, "mot")?.to_string(),
+        deck: deck.to_string(),
+        description: field(node, "description")?.to_string(),
+        link: field(node, "lien")?.to_string(),
+        synonyms: optional_list(node, "synonymes")?,
+        parameters,
+    })
+}
+
+fn load_categorie(
+    ctx: &Context,
+    node: &Yaml,
+    index: usize,
+    policy: LoadPolicy,
+    warnings: &mut Vec<LoadError>,
+) -> Result<Categorie, LoadError> {
+    let error = |kind| ctx.error(node, Some(index), None, kind);
+    if node.as_hash().is_none() {
+        return Err(error(LoadErrorKind::WrongType("categorie", "a mapping")));
+    }
+    let name = field(node, "categorie").map_err(error)?;
+    let entries = match &node["mots"] {
+        Yaml::BadValue => return Err(error(LoadErrorKind::MissingField("mots"))),
+        Yaml::Array(entries) => entries,
+        _ => return Err(error(LoadErrorKind::WrongType("mots", "a list"))),
+    };
+
+    let deck = ctx.deck();
+    let mut words = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        match load_word(entry, &deck) {
+            Ok(word) => words.push(word),
+            Err(kind) => {
+                // Words that are not mappings have no position, use the categorie one
+                let at = if entry.as_hash().is_some() {
+                    entry
+                } else {
+                    node
+                };
+                let err = ctx.error(at, Some(index), Some(i), kind);
+                if policy == LoadPolicy::Strict {
+                    return Err(err);
+                }
+                warnings.push(err);
+            }
+        }
+    }
+
+    // An empty categorie can't be browsed nor learned
+    if words.is_empty() {
+        return Err(error(LoadErrorKind::NoWord));
+    }
+
+    Ok(Categorie {
+        name: name.to_string(),
+        deck,
+        words,
+    })
+}
+
+// Load yaml entries into a vector of categories.
+// With `LoadPolicy::Lenient`, invalid categories and words are
+// skipped and returned as warnings alongside the loaded ones.
+pub fn load_file(
+    file: &str,
+    policy: LoadPolicy,
+) -> Result<(Vec<Categorie>, Vec<LoadError>), LoadError> {
     let mut contents = String::new();
+    File::open(file)
+        .and_then(|mut f| f.read_to_string(&mut contents))
+        .map_err(|e| LoadError {
+            file: file.to_string(),
+            line: 0,
+            col: 0,
+            categorie: None,
+            word: None,
+            kind: LoadErrorKind::Io(e),
+        })?;
+    load_str(file, &contents, policy)
+}
+
+// Load yaml entries already read, `file` only names them in errors
+pub fn load_str(
+    file: &str,
+    contents: &str,
+    policy: LoadPolicy,
+) -> Result<(Vec<Categorie>, Vec<LoadError>), LoadError> {
+    let mut ctx = Context {
+        file,
+        marks: HashMap::new(),
+    };
+    let docs = YamlLoader::load_from_str(contents).map_err(|e| LoadError {
+        file: file.to_string(),
+        line: e.marker().line(),
+        col: e.marker().col() + 1,
+        categorie: None,
+        word: None,
+        // Position is already reported by the error itself
+        kind: LoadErrorKind::Syntax(e.to_string().split(" at line ").next().unwrap().to_string()),
+    })?;
+    let doc = docs.first().unwrap_or(&Yaml::BadValue);
+
+    let mut receiver = MappingMarks {
+        marks: Vec::new(),
+        documents: 0,
+        first_key: false,
+    };
+    // The file has already been parsed successfully above
+    Parser::new(contents.chars())
+        .load(&mut receiver, false)
+        .ok();
+    ctx.index_marks(doc, &receiver.marks, &mut 0);
+
+    let entries = match &doc["categories"] {
+        Yaml::Array(entries) => entries,
+        Yaml::BadValue => {
+            let kind = LoadErrorKind::MissingField("categories");
+            return Err(ctx.error(doc, None, None, kind));
+        }
+        _ => {
+            let kind = LoadErrorKind::WrongType("categories", "a list");
+            return Err(ctx.error(doc, None, None, kind));
+        }
+    };
 
     let mut res = Vec::new();
+    let mut warnings = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        match load_categorie(&ctx, entry, i, policy, &mut warnings) {
+            Ok(categorie) => res.push(categorie),
+            Err(err) if policy == LoadPolicy::Lenient => warnings.push(err),
+            Err(err) => return Err(err),
+        }
+    }
 
-    file.read_to_string(&mut contents)
-        .expect("Unable to read file");
+    Ok((res, warnings))
+}
 
-    let docs = YamlLoader::load_from_str(&contents).unwrap();
-    let doc = &docs[0];
+// Load several files, or directories of `*.yaml` files, into a single
+// vector of categories. Each categorie keeps the name of its deck.
+pub fn load_decks(
+    paths: &[String],
+    policy: LoadPolicy,
+) -> Result<(Vec<Categorie>, Vec<LoadError>), LoadError> {
+    let mut files = Vec::new();
+    for path in paths {
+        if !Path::new(path).is_dir() {
+            files.push(path.to_string());
+            continue;
+        }
 
-    for i in doc["categories"].as_vec().unwrap() {
-        let mut words = Vec::new();
-        for y in i["mots"].as_vec().unwrap() {
-            let word = Word {
-                name: String::from(y["mot"].as_str().unwrap()),
-                description: String::from(y["description"].as_str().unwrap()),
-                link: String::from(y["lien"].as_str().unwrap()),
-            };
-            words.push(word);
+        let io_error = |e| LoadError {
+            file: path.to_string(),
+            line: 0,
+            col: 0,
+            categorie: None,
+            word: None,
+            kind: LoadErrorKind::Io(e),
+        };
+        let mut decks = Vec::new();
+        for entry in fs::read_dir(path).map_err(io_error)? {
+            let entry_path = entry.map_err(io_error)?.path();
+            if entry_path.is_file() && entry_path.extension() == Some(OsStr::new("yaml")) {
+                decks.push(entry_path.to_string_lossy().to_string());
+            }
         }
-        let categorie = Categorie {
-            name: String::from(i["categorie"].as_str().unwrap()),
-            words,
+        // Directory order is arbitrary, keep decks in a stable order
+        decks.sort();
+        files.extend(decks);
+    }
+
+    let mut categories = Vec::new();
+    let mut warnings = Vec::new();
+    // File of each deck loaded so far, reviews are stored by deck name
+    let mut decks: HashMap<String, String> = HashMap::new();
+    for file in files {
+        let deck = deck_name(&file);
+        let loaded = match decks.get(&deck) {
+            Some(other) => Err(LoadError {
+                file: file.to_string(),
+                line: 0,
+                col: 0,
+                categorie: None,
+                word: None,
+                kind: LoadErrorKind::DuplicateDeck(other.to_string()),
+            }),
+            None => load_file(&file, policy),
         };
-        res.push(categorie);
+        match (loaded, policy) {
+            (Ok((mut deck_categories, mut deck_warnings)), _) => {
+                decks.insert(deck, file);
+                categories.append(&mut deck_categories);
+                warnings.append(&mut deck_warnings);
+            }
+            // Files that can't be read or that repeat a deck are skipped as a whole
+            (Err(e), LoadPolicy::Lenient)
+                if matches!(e.kind, LoadErrorKind::Io(_) | LoadErrorKind::DuplicateDeck(_)) =>
+            {
+                warnings.push(e)
+            }
+            (Err(e), _) => return Err(e),
+        }
+    }
+
+    Ok((categories, warnings))
+}
+
+// Name of the deck of a file, its stem
+fn deck_name(file: &str) -> String {
+    Path::new(file)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| file.to_string())
+}
+
+// Categories of words sharing the same value of a parameter
+pub struct Grouping {
+    pub title: &'static str,
+    pub categories: Vec<Categorie>,
+}
+
+// Group words of the given categories by each parameter.
+// The first grouping holds the categories of the files, parameters
+// without any value are left out.
+pub fn group_words(categories: Vec<Categorie>) -> Vec<Grouping> {
+    let mut groupings = Vec::new();
+
+    for parameter in Parameter::ALL.iter() {
+        let mut groups: Vec<Categorie> = Vec::new();
+        for word in categories.iter().flat_map(|c| c.words.iter()) {
+            let value = match word.parameters.get(*parameter) {
+                Some(value) => value,
+                None => continue,
+            };
+            match groups.iter_mut().find(|g| g.name == value) {
+                Some(group) => group.words.push(word.clone()),
+                None => groups.push(Categorie {
+                    name: value.to_string(),
+                    deck: parameter.key().to_string(),
+                    words: vec![word.clone()],
+                }),
+            }
+        }
+        if !groups.is_empty() {
+            groups.sort_by(|a, b| a.name.cmp(&b.name));
+            groupings.push(Grouping {
+                title: parameter.label(),
+                categories: groups,
+            });
+        }
+    }
+
+    groupings.insert(
+        0,
+        Grouping {
+            title: "Categories",
+            categories,
+        },
+    );
+    groupings
+}
+
+// Word with nothing but a name, shared by the tests of every module
+#[cfg(test)]
+impl Word {
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            deck: String::new(),
+            description: String::new(),
+            link: String::new(),
+            synonyms: Vec::new(),
+            parameters: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const DECK: &str = "\
+categories:
+ - categorie: \"Salutations\"
+   mots:
+     - mot: \"Bonjour\"
+       description: \"Main plate sur la bouche\"
+       lien: \"\"
+     - mot: \"Merci\"
+       lien: \"\"
+ - categorie: \"Vide\"
+   mots:
+     - mot: 3
+       description: \"\"
+       lien: \"\"
+";
+
+    const VALID_DECK: &str = "\
+categories:
+ - categorie: \"Salutations\"
+   mots:
+     - mot: \"Bonjour\"
+       description: \"\"
+       lien: \"\"
+";
+
+    #[test]
+    fn strict_reports_the_position_of_the_first_invalid_word() {
+        let err = load_str("deck.yaml", DECK, LoadPolicy::Strict).err().unwrap();
+        assert_eq!((err.line, err.col), (7, 8));
+        assert_eq!((err.categorie, err.word), (Some(0), Some(1)));
+        assert!(matches!(err.kind, LoadErrorKind::MissingField("description")));
+        assert_eq!(
+            err.to_string(),
+            "deck.yaml:7:8: categorie #1, word #2: missing field 'description'"
+        );
+    }
+
+    #[test]
+    fn lenient_skips_invalid_words_and_empty_categories() {
+        let (categories, warnings) = load_str("deck.yaml", DECK, LoadPolicy::Lenient).unwrap();
+        assert_eq!(categories.len(), 1);
+        assert_eq!(categories[0].name, "Salutations");
+        assert_eq!(categories[0].deck, "deck");
+        assert_eq!(categories[0].words.len(), 1);
+
+        let kinds: Vec<String> = warnings.iter().map(|w| w.kind.to_string()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "missing field 'description'",
+                "field 'mot' should be a string",
+                "no valid word",
+            ]
+        );
+        assert_eq!(warnings[2].categorie, Some(1));
+        assert_eq!(warnings[2].word, None);
+    }
+
+    #[test]
+    fn strict_rejects_a_categorie_without_words() {
+        let deck = "categories:\n - categorie: \"Vide\"\n   mots: []\n";
+        let err = load_str("deck.yaml", deck, LoadPolicy::Strict).err().unwrap();
+        assert!(matches!(err.kind, LoadErrorKind::NoWord));
+        assert_eq!((err.line, err.col), (2, 4));
+    }
+
+    // Directory of deck files written for a test
+    fn deck_files(name: &str, files: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("lsf-tui-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for file in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, VALID_DECK).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn lenient_skips_unreadable_files() {
+        let dir = deck_files("unreadable", &["LSF.yaml"]);
+        let paths = [dir.join("missing.yaml"), dir.join("LSF.yaml")]
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect::<Vec<String>>();
+
+        let err = load_decks(&paths, LoadPolicy::Strict).err().unwrap();
+        assert!(matches!(err.kind, LoadErrorKind::Io(_)));
+
+        let (categories, warnings) = load_decks(&paths, LoadPolicy::Lenient).unwrap();
+        assert_eq!(categories.len(), 1);
+        assert!(matches!(warnings[0].kind, LoadErrorKind::Io(_)));
+        assert_eq!(warnings[0].file, paths[0]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn decks_sharing_a_name_are_rejected() {
+        let dir = deck_files("duplicate", &["a/LSF.yaml", "b/LSF.yaml", "b/Signes.yaml"]);
+        let paths = ["a", "b"]
+            .iter()
+            .map(|sub| dir.join(sub).to_string_lossy().to_string())
+            .collect::<Vec<String>>();
+        let first = dir.join("a/LSF.yaml").to_string_lossy().to_string();
+        let second = dir.join("b/LSF.yaml").to_string_lossy().to_string();
+
+        let err = load_decks(&paths, LoadPolicy::Strict).err().unwrap();
+        assert_eq!(err.file, second);
+        assert!(matches!(&err.kind, LoadErrorKind::DuplicateDeck(file) if *file == first));
+
+        let (categories, warnings) = load_decks(&paths, LoadPolicy::Lenient).unwrap();
+        let decks: Vec<&str> = categories.iter().map(|c| c.deck.as_str()).collect();
+        assert_eq!(decks, ["LSF", "Signes"]);
+        let duplicates = warnings
+            .iter()
+            .filter(|w| matches!(w.kind, LoadErrorKind::DuplicateDeck(_)))
+            .count();
+        assert_eq!(duplicates, 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn syntax_errors_have_a_position() {
+        let err = load_str("deck.yaml", "categories: [\n", LoadPolicy::Strict).err().unwrap();
+        assert!(matches!(err.kind, LoadErrorKind::Syntax(_)));
+        assert!(err.line > 0);
     }
-    res
 }
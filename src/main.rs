@@ -3,38 +3,120 @@ extern crate clap;
 
 mod args;
 mod background_mode;
+mod config;
 mod draw;
+mod elix;
 mod event;
+mod favourites;
+mod high_scores;
 mod loader;
+mod numbers;
+mod quiz;
+mod review_log;
+mod scheduler;
+mod search;
 mod selection;
+mod session;
+mod setup;
+mod spelling;
+mod stats;
+mod storage;
+#[cfg(test)]
+mod test_server;
 mod tui_mode;
 mod search_video;
+mod video_cache;
+mod video_frame;
+mod video_source;
 
 use std::io;
+use std::process;
+use std::sync::{Arc, Mutex};
 
 use args::{parse_arguments, Mode};
 use background_mode::background_routine;
-use loader::{load_file, Word};
+use config::Config;
+use loader::{load_decks, LoadError, Word};
+use numbers::{load_rules, NumberDeck};
+use spelling::load_alphabet;
 use tui_mode::tui_routine;
-use search_video::{query_videos, select_videos};
+use search_video::{video_routine, VideoPlayer};
+use video_cache::{prefetch_routine, VideoCache};
+
+// Value loaded from yaml files, after printing the warnings.
+// Exit on the first error.
+fn or_exit<T>(loaded: Result<(T, Vec<LoadError>), LoadError>) -> T {
+    match loaded {
+        Ok((value, warnings)) => {
+            for warning in warnings {
+                eprintln!("warning: {}", warning);
+            }
+            value
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    }
+}
 
 fn main() -> Result<(), io::Error> {
-    // Read yaml file
-    let categories = load_file("LSF.yaml");
+    // Retrieve arguments
+    let arguments = parse_arguments();
+
+    // Read yaml files
+    let categories = or_exit(load_decks(&arguments.yaml, arguments.policy));
+    if categories.is_empty() {
+        eprintln!("error: no categorie to load");
+        process::exit(1);
+    }
+    // Handshapes of the letters, for tab 'Alphabet'
+    let alphabet = or_exit(load_alphabet(arguments.alphabet.as_deref(), arguments.policy));
+    // Base units the numbers of tab 'Learn' are composed of
+    let units = or_exit(load_rules(arguments.numbers.as_deref(), arguments.policy));
+    // Provider of the videos and their output, from the configuration file
+    let video = Config::load().and_then(|config| Ok((config.video_source()?, config.video_output()?)));
+    let (video_source, video_output) = match video {
+        Ok(video) => video,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    };
+    // Offline copy of the videos, shared by every lookup
+    let cache = if video_source.cacheable() {
+        Some(Arc::new(Mutex::new(VideoCache::open(VideoCache::default_dir())?)))
+    } else {
+        None
+    };
+    let player = VideoPlayer::new(video_source.clone(), cache.clone(), video_output);
     let all_words = categories
         .iter()
         .cloned()
         .flat_map(|c| c.words)
         .collect::<Vec<Word>>();
 
-    // Retrieve arguments
-    let arguments = parse_arguments();
     match arguments.mode {
-        Mode::TUI => tui_routine(categories, all_words),
+        Mode::TUI => {
+            tui_routine(categories, alphabet, NumberDeck::new(units), player)
+        }
         Mode::Background(sec) => background_routine(sec, all_words, arguments.description),
         Mode::Video => {
-            let video_urls = query_videos(&arguments.video_word);
-            select_videos(video_urls)
+            // The word may be unknown to the decks
+            let word = all_words
+                .iter()
+                .find(|word| word.name == arguments.video_word)
+                .cloned()
+                .unwrap_or(Word {
+                    name: arguments.video_word,
+                    deck: String::new(),
+                    description: String::new(),
+                    link: String::new(),
+                    synonyms: Vec::new(),
+                    parameters: Default::default(),
+                });
+            video_routine(player, word)
         },
+        Mode::Prefetch => prefetch_routine(video_source.as_ref(), cache.as_deref(), all_words),
     }
 }
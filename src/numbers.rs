@@ -0,0 +1,221 @@
+use rand::prelude::*;
+
+use std::cell::OnceCell;
+
+use crate::loader::{load_file, load_str, LoadError, LoadPolicy, Parameters, Word};
+use crate::quiz::CHOICES;
+
+// Rules used unless another file is given with --numbers
+const RULES: &str = include_str!("../numbers.yaml");
+
+// Bounds of the ranges of numbers offered in the setup pop-up
+pub const BOUNDS: [u32; 7] = [0, 10, 20, 50, 100, 1000, 9999];
+
+// Deck of the generated numbers, to schedule them apart from the words
+pub const NUMBERS_DECK: &str = "numbers";
+
+// Base units of 100 and more multiply the number signed before them
+const MULTIPLIER: u32 = 100;
+
+// Base unit of the numbers and how it is signed
+pub struct Unit {
+    pub value: u32,
+    pub description: String,
+}
+
+// Base units of the given rules file or of the built-in one, largest
+// first. Words whose name is not a number are left out.
+pub fn load_rules(
+    file: Option<&str>,
+    policy: LoadPolicy,
+) -> Result<(Vec<Unit>, Vec<LoadError>), LoadError> {
+    let (categories, warnings) = match file {
+        Some(file) => load_file(file, policy)?,
+        None => load_str("numbers.yaml (built-in)", RULES, policy)?,
+    };
+    let mut units: Vec<Unit> = categories
+        .into_iter()
+        .flat_map(|c| c.words)
+        .filter_map(|word| {
+            Some(Unit {
+                value: word.name.trim().parse().ok()?,
+                description: word.description,
+            })
+        })
+        .collect();
+    units.sort_by_key(|unit| std::cmp::Reverse(unit.value));
+    Ok((units, warnings))
+}
+
+// Units signed one after another to sign a number, from the largest.
+// None if the number cannot be made of the units.
+pub fn compose(number: u32, units: &[Unit]) -> Option<Vec<&Unit>> {
+    if number == 0 {
+        return units.iter().find(|unit| unit.value == 0).map(|unit| vec![unit]);
+    }
+
+    let mut parts = Vec::new();
+    let mut rest = number;
+    while rest > 0 {
+        let unit = units.iter().find(|unit| unit.value > 0 && unit.value <= rest)?;
+        let count = rest / unit.value;
+        if unit.value >= MULTIPLIER {
+            // A single hundred or thousand is signed without its count
+            if count > 1 {
+                parts.extend(compose(count, units)?);
+            }
+            rest %= unit.value;
+        } else {
+            rest -= unit.value;
+        }
+        parts.push(unit);
+    }
+    Some(parts)
+}
+
+// Numbers learned in tab 'Learn', each one a word described by the
+// composition of its sign. Words are composed the first time they are drawn.
+pub struct NumberDeck {
+    units: Vec<Unit>,
+    // Word of each number up to the largest bound, `None` when
+    // the units can't compose it
+    words: Vec<OnceCell<Option<Word>>>,
+}
+
+impl NumberDeck {
+    pub fn new(units: Vec<Unit>) -> Self {
+        let largest = BOUNDS[BOUNDS.len() - 1];
+        Self {
+            units,
+            words: (0..=largest).map(|_| OnceCell::new()).collect(),
+        }
+    }
+
+    pub fn get(&self, number: u32) -> Option<&Word> {
+        self.words
+            .get(number as usize)?
+            .get_or_init(|| {
+                // The values of the units are left out, they would
+                // give the answer away in the reverse direction
+                let description = compose(number, &self.units)?
+                    .iter()
+                    .map(|unit| unit.description.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", puis ");
+                Some(Word {
+                    name: number.to_string(),
+                    deck: NUMBERS_DECK.to_string(),
+                    description,
+                    link: String::new(),
+                    synonyms: Vec::new(),
+                    parameters: Parameters::default(),
+                })
+            })
+            .as_ref()
+    }
+
+    // Words of distinct random numbers between the two bounds, at most
+    // `count` of them or all of them if it is 0, smallest first
+    pub fn draw(&self, smallest: u32, largest: u32, count: usize) -> Vec<&Word> {
+        let mut numbers: Vec<u32> = (smallest..=largest).collect();
+        if count > 0 && count < numbers.len() {
+            numbers = numbers.choose_multiple(&mut rand::thread_rng(), count).copied().collect();
+            numbers.sort_unstable();
+        }
+        numbers.into_iter().filter_map(|number| self.get(number)).collect()
+    }
+
+    // Candidate numbers of the multiple choice quiz, in random order.
+    // Distractors are numbers close to the right one.
+    pub fn choices(&self, word: &Word) -> Vec<String> {
+        let mut rng = rand::thread_rng();
+        let number: u32 = word.name.parse().unwrap_or(0);
+        let spread = (number / 10).max(CHOICES as u32);
+        let near: Vec<u32> = (number.saturating_sub(spread)..=number + spread)
+            .filter(|other| *other != number && self.get(*other).is_some())
+            .collect();
+
+        let mut choices: Vec<String> = near
+            .choose_multiple(&mut rng, CHOICES - 1)
+            .map(|other| other.to_string())
+            .collect();
+        choices.push(word.name.to_string());
+        choices.shuffle(&mut rng);
+        choices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn units() -> Vec<Unit> {
+        load_rules(None, LoadPolicy::Strict).unwrap().0
+    }
+
+    fn values(number: u32, units: &[Unit]) -> Option<Vec<u32>> {
+        compose(number, units).map(|parts| parts.iter().map(|unit| unit.value).collect())
+    }
+
+    #[test]
+    fn compose_signs_the_largest_units_first() {
+        let units = units();
+        assert_eq!(values(0, &units), Some(vec![0]));
+        assert_eq!(values(7, &units), Some(vec![7]));
+        assert_eq!(values(15, &units), Some(vec![10, 5]));
+        assert_eq!(values(347, &units), Some(vec![3, 100, 40, 7]));
+        assert_eq!(values(1000, &units), Some(vec![1000]));
+        assert_eq!(values(2024, &units), Some(vec![2, 1000, 20, 4]));
+        assert_eq!(values(9999, &units), Some(vec![9, 1000, 9, 100, 90, 9]));
+    }
+
+    #[test]
+    fn compose_fails_without_the_needed_units() {
+        let units = vec![Unit {
+            value: 10,
+            description: String::new(),
+        }];
+        assert_eq!(values(0, &units), None);
+        assert_eq!(values(15, &units), None);
+        assert_eq!(values(30, &units), Some(vec![10, 10, 10]));
+    }
+
+    #[test]
+    fn deck_describes_numbers_without_their_values() {
+        let rules = units();
+        let description = |value: u32| {
+            let unit = rules.iter().find(|unit| unit.value == value).unwrap();
+            unit.description.to_string()
+        };
+        let expected = [3, 100, 40, 7].iter().map(|value| description(*value)).collect::<Vec<_>>();
+
+        let deck = NumberDeck::new(units());
+        let word = deck.get(347).unwrap();
+        assert_eq!(word.name, "347");
+        assert_eq!(word.deck, NUMBERS_DECK);
+        assert_eq!(word.description, expected.join(", puis "));
+        assert!(!word.description.contains("347"));
+        assert!(deck.get(BOUNDS[BOUNDS.len() - 1] + 1).is_none());
+    }
+
+    #[test]
+    fn deck_draws_distinct_numbers_of_the_range() {
+        let deck = NumberDeck::new(units());
+        let numbers: Vec<u32> = deck
+            .draw(10, 20, 5)
+            .iter()
+            .map(|word| word.name.parse().unwrap())
+            .collect();
+        assert_eq!(numbers.len(), 5);
+        assert!(numbers.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(numbers.iter().all(|number| (10..=20).contains(number)));
+        assert_eq!(deck.draw(0, 10, 0).len(), 11);
+
+        let word = deck.get(0).unwrap();
+        let mut choices = deck.choices(word);
+        assert!(choices.contains(&word.name));
+        choices.sort();
+        choices.dedup();
+        assert_eq!(choices.len(), CHOICES);
+    }
+}
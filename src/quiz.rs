@@ -0,0 +1,380 @@
+use rand::prelude::*;
+
+use crate::draw::WordState;
+use crate::loader::{Categorie, Word};
+use crate::numbers::NUMBERS_DECK;
+use crate::scheduler::Grade;
+use crate::search::normalize;
+
+// Number of words suggested while the answer is typed
+const SUGGESTIONS: usize = 5;
+
+// Number of candidate words of the multiple choice quiz
+pub const CHOICES: usize = 4;
+
+// Exercise of tab 'Learn'
+#[derive(Clone, Copy, PartialEq)]
+pub enum LearnMode {
+    // The word is shown and the learner recalls its sign
+    Recall,
+    // The sign is played and the learner finds the word
+    Recognition,
+    // The description is shown and the learner types the word
+    Typed,
+    // The description is shown and the learner picks the word
+    Choice,
+}
+
+impl LearnMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            LearnMode::Recall => "recall",
+            LearnMode::Recognition => "recognition",
+            LearnMode::Typed => "typed",
+            LearnMode::Choice => "choice",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "recall" => Some(LearnMode::Recall),
+            "recognition" => Some(LearnMode::Recognition),
+            "typed" => Some(LearnMode::Typed),
+            "choice" => Some(LearnMode::Choice),
+            _ => None,
+        }
+    }
+}
+
+// How close an answer is to the word
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Verdict {
+    Right,
+    // Accepted with a few wrong letters
+    Typo,
+    Wrong,
+}
+
+impl Verdict {
+    pub fn grade(self) -> Grade {
+        match self {
+            Verdict::Right => Grade::Good,
+            Verdict::Typo => Grade::Hard,
+            Verdict::Wrong => Grade::Again,
+        }
+    }
+}
+
+// Step of the correction of an answer, letter by letter
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Edit {
+    Same(char),
+    // Letter typed but not in the word
+    Extra(char),
+    // Letter of the word not typed
+    Missing(char),
+    // Letter typed instead of the letter of the word
+    Wrong(char, char),
+}
+
+impl Edit {
+    fn is_same(self) -> bool {
+        matches!(self, Edit::Same(_))
+    }
+}
+
+// Shortest list of edits turning the answer into the expected text
+// (Levenshtein distance with backtracking)
+pub fn diff(answer: &str, expected: &str) -> Vec<Edit> {
+    let a: Vec<char> = answer.chars().collect();
+    let b: Vec<char> = expected.chars().collect();
+
+    // costs[i][j] is the distance between a[i..] and b[j..]
+    let mut costs = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in (0..=a.len()).rev() {
+        for j in (0..=b.len()).rev() {
+            costs[i][j] = if i == a.len() {
+                b.len() - j
+            } else if j == b.len() {
+                a.len() - i
+            } else {
+                let replace = costs[i + 1][j + 1] + usize::from(a[i] != b[j]);
+                replace.min(costs[i + 1][j] + 1).min(costs[i][j + 1] + 1)
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && costs[i][j] == costs[i + 1][j + 1] + usize::from(a[i] != b[j]) {
+            edits.push(if a[i] == b[j] {
+                Edit::Same(a[i])
+            } else {
+                Edit::Wrong(a[i], b[j])
+            });
+            i += 1;
+            j += 1;
+        } else if i < a.len() && costs[i][j] == costs[i + 1][j] + 1 {
+            edits.push(Edit::Extra(a[i]));
+            i += 1;
+        } else {
+            edits.push(Edit::Missing(b[j]));
+            j += 1;
+        }
+    }
+    edits
+}
+
+// Number of wrong letters accepted in a word of the given length
+fn tolerance(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+// Compare an answer with a word and its synonyms, ignoring accents, case
+// and apostrophes. Return the verdict and the correction against the
+// closest of them. A wrong digit is never a typo, numbers must be exact.
+pub fn check(answer: &str, word: &Word) -> (Verdict, Vec<Edit>) {
+    let answer = normalize(answer);
+    let (expected, edits) = std::iter::once(&word.name)
+        .chain(word.synonyms.iter())
+        .map(|expected| {
+            let expected = normalize(expected);
+            let edits = diff(&answer, &expected);
+            (expected, edits)
+        })
+        .min_by_key(|(_, edits)| edits.iter().filter(|edit| !edit.is_same()).count())
+        .unwrap_or_default();
+
+    let distance = edits.iter().filter(|edit| !edit.is_same()).count();
+    let verdict = if distance == 0 {
+        Verdict::Right
+    } else if word.deck != NUMBERS_DECK && distance <= tolerance(expected.chars().count()) {
+        Verdict::Typo
+    } else {
+        Verdict::Wrong
+    };
+    (verdict, edits)
+}
+
+// Candidate words of the multiple choice quiz, in random order.
+// Distractors come preferably from the categorie of the word, then
+// from words sharing its handshape or its location.
+pub fn choices(word: &Word, categories: &[Categorie]) -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    let same = |a: &Option<String>, b: &Option<String>| a.is_some() && a == b;
+    let answer = normalize(&word.name);
+
+    let mut candidates: Vec<(u32, &str)> = Vec::new();
+    for categorie in categories {
+        let in_categorie = categorie.words.iter().any(|w| w.name == word.name);
+        for other in categorie.words.iter() {
+            // A distractor must not be another right answer
+            if normalize(&other.name) == answer
+                || word.synonyms.iter().any(|s| normalize(s) == normalize(&other.name))
+            {
+                continue;
+            }
+            let score = 2 * u32::from(in_categorie)
+                + u32::from(same(&other.parameters.configuration, &word.parameters.configuration))
+                + u32::from(same(&other.parameters.emplacement, &word.parameters.emplacement));
+            match candidates.iter_mut().find(|(_, name)| *name == other.name) {
+                Some(candidate) => candidate.0 = candidate.0.max(score),
+                None => candidates.push((score, &other.name)),
+            }
+        }
+    }
+    // Shuffle then sort, so that equal scores are drawn at random
+    candidates.shuffle(&mut rng);
+    candidates.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    let mut choices: Vec<String> = candidates
+        .into_iter()
+        .take(CHOICES - 1)
+        .map(|(_, name)| name.to_string())
+        .collect();
+    choices.push(word.name.to_string());
+    choices.shuffle(&mut rng);
+    choices
+}
+
+// Result of the last answer, displayed with the next word
+pub struct Feedback {
+    pub word: String,
+    pub verdict: Verdict,
+    // Correction of the answer, letter by letter
+    pub edits: Vec<Edit>,
+}
+
+// Answer of the learner in the recognition quiz, in typed mode
+// and in the multiple choice quiz
+pub struct Answer {
+    pub text: String,
+    // Index of the highlighted suggestion, if any
+    pub choice: Option<usize>,
+    // Candidate words of the multiple choice quiz
+    pub choices: Vec<String>,
+    pub feedback: Option<Feedback>,
+}
+
+impl Answer {
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            choice: None,
+            choices: Vec::new(),
+            feedback: None,
+        }
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.text.push(c);
+        self.choice = None;
+    }
+
+    pub fn pop(&mut self) {
+        self.text.pop();
+        self.choice = None;
+    }
+
+    // Words of the session containing the typed answer
+    pub fn suggestions<'a>(&self, words: &[(&'a Word, WordState)]) -> Vec<&'a str> {
+        let query = normalize(&self.text);
+        let mut suggestions: Vec<&str> = Vec::new();
+        if query.is_empty() {
+            return suggestions;
+        }
+        for (word, _) in words {
+            if suggestions.len() == SUGGESTIONS {
+                break;
+            }
+            if !suggestions.contains(&word.name.as_str()) && normalize(&word.name).contains(&query) {
+                suggestions.push(&word.name);
+            }
+        }
+        suggestions
+    }
+
+    pub fn down(&mut self, count: usize) {
+        if count > 0 {
+            self.choice = Some(self.choice.map_or(0, |i| (i + 1) % count));
+        }
+    }
+
+    pub fn up(&mut self, count: usize) {
+        if count > 0 {
+            self.choice = Some(self.choice.map_or(count - 1, |i| (count + i - 1) % count));
+        }
+    }
+
+    // Check the highlighted suggestion, or the typed text, against the word.
+    // The answer is cleared and the result kept as feedback.
+    pub fn submit(&mut self, word: &Word, suggestions: &[&str]) -> Verdict {
+        let answer = match self.choice {
+            Some(i) => suggestions[i].to_string(),
+            None => self.text.trim().to_string(),
+        };
+        let (verdict, edits) = check(&answer, word);
+        self.feedback = Some(Feedback {
+            word: word.name.to_string(),
+            verdict,
+            edits,
+        });
+        self.text.clear();
+        self.choice = None;
+        verdict
+    }
+
+    // Check a candidate of the multiple choice quiz, only the word is right
+    pub fn choose(&mut self, index: usize, word: &Word) -> Option<Verdict> {
+        let choice = self.choices.get(index)?;
+        let verdict = if *choice == word.name {
+            Verdict::Right
+        } else {
+            Verdict::Wrong
+        };
+        self.feedback = Some(Feedback {
+            word: word.name.to_string(),
+            verdict,
+            edits: Vec::new(),
+        });
+        Some(verdict)
+    }
+
+    // The countdown of a challenge is over, the word is failed
+    pub fn time_out(&mut self, word: &Word) {
+        self.feedback = Some(Feedback {
+            word: word.name.to_string(),
+            verdict: Verdict::Wrong,
+            edits: Vec::new(),
+        });
+        self.text.clear();
+        self.choice = None;
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_lists_the_edits_letter_by_letter() {
+        use Edit::*;
+        assert_eq!(diff("chat", "chat"), [Same('c'), Same('h'), Same('a'), Same('t')]);
+        assert_eq!(
+            diff("chat", "chaton"),
+            [Same('c'), Same('h'), Same('a'), Same('t'), Missing('o'), Missing('n')]
+        );
+        assert_eq!(diff("chaat", "chat"), [Same('c'), Same('h'), Same('a'), Extra('a'), Same('t')]);
+        assert_eq!(diff("rat", "rit"), [Same('r'), Wrong('a', 'i'), Same('t')]);
+        assert_eq!(diff("", "il"), [Missing('i'), Missing('l')]);
+    }
+
+    #[test]
+    fn check_ignores_accents_case_and_apostrophes() {
+        assert_eq!(check("ecole", &Word::named("École")).0, Verdict::Right);
+        assert_eq!(check("Sil te plait", &Word::named("S'il te plaît")).0, Verdict::Right);
+    }
+
+    #[test]
+    fn check_tolerates_typos_by_length() {
+        // Up to 3 letters, no wrong letter
+        assert_eq!(check("amy", &Word::named("ami")).0, Verdict::Wrong);
+        // From 4 to 7 letters, one wrong letter
+        assert_eq!(check("maizon", &Word::named("maison")).0, Verdict::Typo);
+        assert_eq!(check("mezon", &Word::named("maison")).0, Verdict::Wrong);
+        // From 8 letters, two wrong letters
+        assert_eq!(check("ordinatuer", &Word::named("ordinateur")).0, Verdict::Typo);
+        assert_eq!(check("ordinatuers", &Word::named("ordinateur")).0, Verdict::Wrong);
+    }
+
+    #[test]
+    fn check_requires_exact_numbers() {
+        let mut number = Word::named("1234");
+        number.deck = NUMBERS_DECK.to_string();
+        assert_eq!(check("1234", &number).0, Verdict::Right);
+        assert_eq!(check("1235", &number).0, Verdict::Wrong);
+        // The same text in a deck is a word, typos are tolerated
+        assert_eq!(check("1235", &Word::named("1234")).0, Verdict::Typo);
+    }
+
+    #[test]
+    fn check_corrects_against_the_closest_synonym() {
+        let car = Word {
+            synonyms: vec![String::from("auto")],
+            ..Word::named("voiture")
+        };
+        assert_eq!(check("Auto", &car).0, Verdict::Right);
+        let (verdict, edits) = check("autos", &car);
+        assert_eq!(verdict, Verdict::Typo);
+        assert_eq!(edits.last(), Some(&Edit::Extra('s')));
+    }
+}
@@ -0,0 +1,81 @@
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use yaml_rust::yaml::Hash;
+use yaml_rust::Yaml;
+
+use crate::scheduler::{CardDirection, Grade};
+use crate::storage::{append_yaml, data_dir, load_yaml_stream};
+
+// A review made in tab 'Learn'
+pub struct LogEntry {
+    pub word: String,
+    pub deck: String,
+    pub grade: Grade,
+    pub direction: CardDirection,
+    // Time of the review in seconds since epoch
+    pub time: u64,
+    // Time spent on the word
+    pub spent: Duration,
+}
+
+impl LogEntry {
+    fn to_yaml(&self) -> Yaml {
+        let mut h = Hash::new();
+        h.insert(Yaml::from_str("word"), Yaml::String(self.word.to_string()));
+        h.insert(Yaml::from_str("deck"), Yaml::String(self.deck.to_string()));
+        h.insert(Yaml::from_str("grade"), Yaml::from_str(self.grade.name()));
+        h.insert(
+            Yaml::from_str("direction"),
+            Yaml::from_str(self.direction.name()),
+        );
+        h.insert(Yaml::from_str("time"), Yaml::Integer(self.time as i64));
+        h.insert(
+            Yaml::from_str("spent"),
+            Yaml::Integer(self.spent.as_millis() as i64),
+        );
+        Yaml::Hash(h)
+    }
+
+    fn from_yaml(node: &Yaml) -> Option<Self> {
+        Some(Self {
+            word: node["word"].as_str()?.to_string(),
+            deck: node["deck"].as_str()?.to_string(),
+            grade: Grade::from_name(node["grade"].as_str()?)?,
+            // Reviews logged before reverse cards were all forward
+            direction: node["direction"]
+                .as_str()
+                .and_then(CardDirection::from_name)
+                .unwrap_or(CardDirection::Forward),
+            time: node["time"].as_i64()? as u64,
+            spent: Duration::from_millis(node["spent"].as_i64()? as u64),
+        })
+    }
+}
+
+// History of every review, stored in the data directory
+// with one yaml document per review
+pub struct ReviewLog {
+    path: PathBuf,
+    pub entries: Vec<LogEntry>,
+}
+
+impl ReviewLog {
+    pub fn load() -> Result<Self, io::Error> {
+        let path = data_dir().join("log.yaml");
+        let entries = load_yaml_stream(&path)?
+            .iter()
+            .filter_map(LogEntry::from_yaml)
+            .collect();
+
+        Ok(Self { path, entries })
+    }
+
+    // Add a review at the end of the log
+    pub fn append(&mut self, entry: LogEntry) -> Result<(), io::Error> {
+        append_yaml(&self.path, &entry.to_yaml())?;
+        self.entries.push(entry);
+        Ok(())
+    }
+}
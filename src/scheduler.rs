@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+use yaml_rust::yaml::Hash;
+use yaml_rust::Yaml;
+
+use crate::favourites::Favourites;
+use crate::high_scores::HighScores;
+use crate::loader::Word;
+use crate::review_log::{LogEntry, ReviewLog};
+use crate::storage::{data_dir, load_yaml, save_yaml, today};
+
+// Self-assessment of the learner after a review
+#[derive(Clone, Copy, PartialEq)]
+pub enum Grade {
+    // The word was not known at all
+    Again,
+    // The word was found with difficulty or by peeking at the help
+    Hard,
+    Good,
+    Easy,
+}
+
+impl Grade {
+    // Quality of the answer for SM-2, from 0 (blackout) to 5 (perfect)
+    pub fn quality(self) -> u8 {
+        match self {
+            Grade::Again => 1,
+            Grade::Hard => 3,
+            Grade::Good => 4,
+            Grade::Easy => 5,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Grade::Again => "again",
+            Grade::Hard => "hard",
+            Grade::Good => "good",
+            Grade::Easy => "easy",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "again" => Some(Grade::Again),
+            "hard" => Some(Grade::Hard),
+            "good" => Some(Grade::Good),
+            "easy" => Some(Grade::Easy),
+            _ => None,
+        }
+    }
+}
+
+// Side of the card shown to the learner, each direction
+// of a word is scheduled on its own
+#[derive(Clone, Copy, PartialEq)]
+pub enum CardDirection {
+    // The word is shown, the learner recalls its sign
+    Forward,
+    // The description or the sign is shown, the learner recalls the word
+    Reverse,
+}
+
+impl CardDirection {
+    pub fn name(self) -> &'static str {
+        match self {
+            CardDirection::Forward => "forward",
+            CardDirection::Reverse => "reverse",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "forward" => Some(CardDirection::Forward),
+            "reverse" => Some(CardDirection::Reverse),
+            _ => None,
+        }
+    }
+}
+
+// Review state of a word, as defined by the SM-2 algorithm
+#[derive(Clone)]
+pub struct Review {
+    pub ease: f64,
+    // Days until the next review
+    pub interval: u64,
+    // Number of successful reviews in a row
+    pub repetitions: u64,
+    // Day (since epoch) of the next review
+    pub due: u64,
+    // Last grade and its time in seconds since epoch
+    pub last: Option<(Grade, u64)>,
+}
+
+impl Default for Review {
+    fn default() -> Self {
+        Self {
+            ease: 2.5,
+            interval: 0,
+            repetitions: 0,
+            due: 0,
+            last: None,
+        }
+    }
+}
+
+impl Review {
+    // Update the state after a review graded from 0 (blackout) to 5 (perfect)
+    pub fn grade(&mut self, quality: u8, day: u64) {
+        let quality = quality.min(5);
+        if quality < 3 {
+            self.repetitions = 0;
+            self.interval = 1;
+        } else {
+            self.repetitions += 1;
+            self.interval = match self.repetitions {
+                1 => 1,
+                2 => 6,
+                _ => (self.interval as f64 * self.ease).round() as u64,
+            };
+        }
+
+        let miss = f64::from(5 - quality);
+        self.ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(1.3);
+        self.due = day + self.interval;
+    }
+
+    fn to_yaml(&self) -> Yaml {
+        let mut h = Hash::new();
+        h.insert(
+            Yaml::from_str("ease"),
+            Yaml::Real(format!("{:.2}", self.ease)),
+        );
+        h.insert(
+            Yaml::from_str("interval"),
+            Yaml::Integer(self.interval as i64),
+        );
+        h.insert(
+            Yaml::from_str("repetitions"),
+            Yaml::Integer(self.repetitions as i64),
+        );
+        h.insert(Yaml::from_str("due"), Yaml::Integer(self.due as i64));
+        if let Some((grade, time)) = self.last {
+            h.insert(Yaml::from_str("grade"), Yaml::from_str(grade.name()));
+            h.insert(Yaml::from_str("reviewed"), Yaml::Integer(time as i64));
+        }
+        Yaml::Hash(h)
+    }
+
+    fn from_yaml(node: &Yaml) -> Option<Self> {
+        Some(Self {
+            ease: node["ease"].as_f64()?,
+            interval: node["interval"].as_i64()? as u64,
+            repetitions: node["repetitions"].as_i64()? as u64,
+            due: node["due"].as_i64()? as u64,
+            last: node["grade"]
+                .as_str()
+                .and_then(Grade::from_name)
+                .zip(node["reviewed"].as_i64().map(|t| t as u64)),
+        })
+    }
+}
+
+// Reviews of each word, by deck then by name
+type Reviews = HashMap<String, HashMap<String, Review>>;
+
+fn reviews_from_yaml(node: &Yaml) -> Reviews {
+    let mut reviews = Reviews::new();
+    if let Some(decks) = node.as_hash() {
+        for (deck, words) in decks {
+            let (deck, words) = match (deck.as_str(), words.as_hash()) {
+                (Some(deck), Some(words)) => (deck, words),
+                _ => continue,
+            };
+            for (name, node) in words {
+                if let (Some(name), Some(review)) = (name.as_str(), Review::from_yaml(node)) {
+                    reviews
+                        .entry(deck.to_string())
+                        .or_default()
+                        .insert(name.to_string(), review);
+                }
+            }
+        }
+    }
+    reviews
+}
+
+fn reviews_to_yaml(reviews: &Reviews) -> Yaml {
+    // Sort decks and words to keep the file readable and diffable
+    let mut decks: Vec<&String> = reviews.keys().collect();
+    decks.sort();
+
+    let mut doc = Hash::new();
+    for deck in decks {
+        let mut names: Vec<&String> = reviews[deck].keys().collect();
+        names.sort();
+
+        let mut words = Hash::new();
+        for name in names {
+            words.insert(Yaml::String(name.to_string()), reviews[deck][name].to_yaml());
+        }
+        doc.insert(Yaml::String(deck.to_string()), Yaml::Hash(words));
+    }
+    Yaml::Hash(doc)
+}
+
+// Review state of the word of a logged review, created if it was never reviewed
+fn card<'a>(reviews: &'a mut Reviews, entry: &LogEntry) -> &'a mut Review {
+    reviews
+        .entry(entry.deck.to_string())
+        .or_default()
+        .entry(entry.word.to_string())
+        .or_default()
+}
+
+fn grade_card(reviews: &mut Reviews, entry: &LogEntry) {
+    let review = card(reviews, entry);
+    review.grade(entry.grade.quality(), entry.time / 86400);
+    review.last = Some((entry.grade, entry.time));
+}
+
+// Persistent review state of every word, history of the reviews,
+// favourite words and high scores, stored in the data directory
+pub struct Scheduler {
+    path: PathBuf,
+    reviews: Reviews,
+    // Reviews of the reverse direction, from description or sign to word
+    reverse: Reviews,
+    pub log: ReviewLog,
+    pub favourites: Favourites,
+    pub high_scores: HighScores,
+}
+
+impl Scheduler {
+    pub fn load() -> Result<Self, io::Error> {
+        let path = data_dir().join("reviews.yaml");
+        let mut reviews = Reviews::new();
+        let mut reverse = Reviews::new();
+
+        if let Some(doc) = load_yaml(&path)? {
+            reviews = reviews_from_yaml(&doc["words"]);
+            reverse = reviews_from_yaml(&doc["reverse"]);
+        }
+
+        // The state is saved when quitting, reviews logged after it
+        // was last saved are graded again
+        let log = ReviewLog::load()?;
+        for entry in log.entries.iter() {
+            let reviews = match entry.direction {
+                CardDirection::Forward => &mut reviews,
+                CardDirection::Reverse => &mut reverse,
+            };
+            let saved = card(reviews, entry).last;
+            if !matches!(saved, Some((_, time)) if time >= entry.time) {
+                grade_card(reviews, entry);
+            }
+        }
+
+        Ok(Self {
+            path,
+            reviews,
+            reverse,
+            log,
+            favourites: Favourites::load()?,
+            high_scores: HighScores::load()?,
+        })
+    }
+
+    pub fn save(&self) -> Result<(), io::Error> {
+        let mut doc = Hash::new();
+        doc.insert(Yaml::from_str("words"), reviews_to_yaml(&self.reviews));
+        doc.insert(Yaml::from_str("reverse"), reviews_to_yaml(&self.reverse));
+        save_yaml(&self.path, &Yaml::Hash(doc))
+    }
+
+    fn reviews(&self, direction: CardDirection) -> &Reviews {
+        match direction {
+            CardDirection::Forward => &self.reviews,
+            CardDirection::Reverse => &self.reverse,
+        }
+    }
+
+    pub fn get(&self, word: &Word, direction: CardDirection) -> Option<&Review> {
+        self.reviews(direction).get(&word.deck)?.get(&word.name)
+    }
+
+    // Words never reviewed are due
+    pub fn is_due(&self, word: &Word, direction: CardDirection) -> bool {
+        match self.get(word, direction) {
+            Some(review) => review.due <= today(),
+            None => true,
+        }
+    }
+
+    // Record a review and add it to the history, the new state
+    // is saved when quitting
+    pub fn review(&mut self, entry: LogEntry) -> Result<(), io::Error> {
+        let reviews = match entry.direction {
+            CardDirection::Forward => &mut self.reviews,
+            CardDirection::Reverse => &mut self.reverse,
+        };
+        grade_card(reviews, &entry);
+        self.log.append(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_grow_with_good_answers() {
+        let mut review = Review::default();
+        review.grade(Grade::Good.quality(), 100);
+        assert_eq!((review.repetitions, review.interval, review.due), (1, 1, 101));
+        review.grade(Grade::Good.quality(), 101);
+        assert_eq!((review.repetitions, review.interval, review.due), (2, 6, 107));
+        review.grade(Grade::Good.quality(), 107);
+        assert_eq!((review.repetitions, review.interval, review.due), (3, 15, 122));
+        // A good answer keeps the ease
+        assert!((review.ease - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ease_follows_the_quality() {
+        let mut easy = Review::default();
+        easy.grade(Grade::Easy.quality(), 0);
+        assert!((easy.ease - 2.6).abs() < 1e-9);
+
+        let mut hard = Review::default();
+        hard.grade(Grade::Hard.quality(), 0);
+        assert!((hard.ease - 2.36).abs() < 1e-9);
+        assert_eq!(hard.repetitions, 1);
+    }
+
+    #[test]
+    fn failure_starts_again() {
+        let mut review = Review::default();
+        for day in 0..3 {
+            review.grade(Grade::Good.quality(), day);
+        }
+        review.grade(Grade::Again.quality(), 10);
+        assert_eq!((review.repetitions, review.interval, review.due), (0, 1, 11));
+        assert!((review.ease - 1.96).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ease_has_a_floor() {
+        let mut review = Review::default();
+        for day in 0..10 {
+            review.grade(0, day);
+        }
+        assert!((review.ease - 1.3).abs() < 1e-9);
+
+        // Qualities above 5 count as perfect
+        let mut over = Review::default();
+        over.grade(9, 0);
+        assert!((over.ease - 2.6).abs() < 1e-9);
+    }
+}
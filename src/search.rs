@@ -0,0 +1,135 @@
+use tui::widgets::ListState;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::loader::Categorie;
+
+// Lower case text without accents nor apostrophes, other punctuation
+// is replaced by spaces so that "S'il te plaît" and "sil te plait" match
+pub fn normalize(text: &str) -> String {
+    let text: String = text
+        .nfd()
+        .filter(|c| !is_combining_mark(*c) && *c != '\'' && *c != '’')
+        .flat_map(char::to_lowercase)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+// Score of a normalized text for a normalized query, higher is better.
+// A fuzzy search also accepts the characters of the query spread in the text.
+fn score(text: &str, query: &str, fuzzy: bool) -> Option<u32> {
+    if text == query {
+        return Some(4);
+    }
+    if text.starts_with(query) {
+        return Some(3);
+    }
+    if text.contains(query) {
+        return Some(2);
+    }
+    if !fuzzy {
+        return None;
+    }
+    // Every character of the query appears in the text, in order
+    let mut chars = text.chars();
+    if query
+        .chars()
+        .filter(|c| *c != ' ')
+        .all(|q| chars.any(|c| c == q))
+    {
+        return Some(1);
+    }
+    None
+}
+
+// This structure holds the search prompt of tab 'Dictionary'
+pub struct Search {
+    pub query: String,
+    // Categorie and word indexes of the matching words, best first
+    pub hits: Vec<(usize, usize)>,
+    pub state: ListState,
+}
+
+impl Search {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            hits: Vec::new(),
+            state: ListState::default(),
+        }
+    }
+
+    // Filter words of every categorie with the current query.
+    // Names weigh more than descriptions, which must contain the query.
+    pub fn update(&mut self, categories: &[Categorie]) {
+        let query = normalize(&self.query);
+        let mut hits: Vec<(u32, usize, usize)> = Vec::new();
+
+        if !query.is_empty() {
+            for (i, categorie) in categories.iter().enumerate() {
+                for (j, word) in categorie.words.iter().enumerate() {
+                    let name = score(&normalize(&word.name), &query, true).map(|s| s * 2);
+                    let description = score(&normalize(&word.description), &query, false);
+                    if let Some(s) = name.max(description) {
+                        hits.push((s, i, j));
+                    }
+                }
+            }
+        }
+        // Stable sort keeps the order of the file for equal scores
+        hits.sort_by_key(|(s, _, _)| std::cmp::Reverse(*s));
+
+        self.hits = hits.into_iter().map(|(_, i, j)| (i, j)).collect();
+        self.state
+            .select(if self.hits.is_empty() { None } else { Some(0) });
+    }
+
+    pub fn down(&mut self) {
+        if let Some(i) = self.state.selected() {
+            self.state.select(Some((i + 1) % self.hits.len()));
+        }
+    }
+
+    pub fn up(&mut self) {
+        if let Some(i) = self.state.selected() {
+            self.state
+                .select(Some((self.hits.len() + i - 1) % self.hits.len()));
+        }
+    }
+
+    // Categorie and word indexes of the highlighted hit
+    pub fn selected(&self) -> Option<(usize, usize)> {
+        self.state.selected().map(|i| self.hits[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_drops_accents_case_and_apostrophes() {
+        assert_eq!(normalize("S'il te plaît"), "sil te plait");
+        assert_eq!(normalize("Aujourd’hui"), "aujourdhui");
+        assert_eq!(normalize("ÉCOLE"), "ecole");
+        assert_eq!(normalize("Œuf"), "œuf");
+    }
+
+    #[test]
+    fn normalize_turns_punctuation_into_single_spaces() {
+        assert_eq!(normalize("  Arc-en-ciel !"), "arc en ciel");
+        assert_eq!(normalize("1, 2,  3"), "1 2 3");
+        assert_eq!(normalize("?!"), "");
+    }
+
+    #[test]
+    fn score_prefers_exact_then_prefix_then_substring() {
+        assert_eq!(score("pomme", "pomme", false), Some(4));
+        assert_eq!(score("pomme de terre", "pomme", false), Some(3));
+        assert_eq!(score("une pomme", "pomme", false), Some(2));
+        assert_eq!(score("pamplemousse", "pms", false), None);
+        assert_eq!(score("pamplemousse", "pms", true), Some(1));
+        assert_eq!(score("pamplemousse", "spm", true), None);
+    }
+}
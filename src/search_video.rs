@@ -1,75 +1,650 @@
 use gstreamer::prelude::*;
-use regex::Regex;
+use termion::event::Key;
+use termion::raw::IntoRawMode;
+use tui::backend::TermionBackend;
+use tui::widgets::ListState;
+use tui::Terminal;
 
+use std::error::Error;
+use std::fmt;
 use std::io;
+use std::process;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
 
+use crate::draw::draw_video_player;
+use crate::video_frame::{clear_image, Frame, Graphics};
+use crate::event::{Event, Events};
+use crate::loader::Word;
+use crate::video_cache::{find_meanings, VideoCache};
+use crate::video_source::{all_videos, Meaning, Video, VideoSource};
 
-pub fn select_videos(urls: Vec<String>) -> Result<(), io::Error> {
-    println!("{} videos found", urls.len());
-    if urls.is_empty() {
-        return Ok(())
+// Why a video could not be found or played
+#[derive(Debug)]
+pub enum VideoError {
+    Http(reqwest::Error),
+    // Page of the dictionary whose videos could not be extracted
+    Markup(String),
+    // Link of a word to a page rather than to a video file
+    NotVideo(String),
+    Playback(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for VideoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VideoError::Http(e) => write!(f, "cannot reach the dictionary: {}", e),
+            VideoError::Markup(page) => write!(f, "unrecognized page layout: {}", page),
+            VideoError::NotVideo(link) => write!(f, "link is not a video file: {}", link),
+            VideoError::Playback(e) => write!(f, "cannot play the video: {}", e),
+            VideoError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for VideoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            VideoError::Http(e) => Some(e),
+            VideoError::Markup(_) | VideoError::NotVideo(_) | VideoError::Playback(_) => None,
+            VideoError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for VideoError {
+    fn from(e: reqwest::Error) -> Self {
+        VideoError::Http(e)
+    }
+}
+
+impl From<io::Error> for VideoError {
+    fn from(e: io::Error) -> Self {
+        VideoError::Io(e)
+    }
+}
+
+fn playback_error<E: fmt::Display>(e: E) -> VideoError {
+    VideoError::Playback(e.to_string())
+}
+
+// Progress of the video lookup started in tab 'Dictionary'
+pub enum VideoStatus {
+    Idle,
+    Searching(String),
+    Playing(String),
+    NotFound(String),
+    // Word and error
+    Failed(String, String),
+}
+
+impl VideoStatus {
+    // A single lookup runs at a time
+    pub fn is_busy(&self) -> bool {
+        matches!(self, VideoStatus::Searching(_) | VideoStatus::Playing(_))
+    }
+}
+
+// Where videos are played
+#[derive(Clone, Copy)]
+pub enum VideoOutput {
+    // Window opened by gstreamer
+    Window,
+    // Frames drawn in the terminal
+    Terminal(Graphics),
+}
+
+// Message of the video workers
+pub enum VideoEvent {
+    Status(VideoStatus),
+    // Last frame of the video played in the terminal
+    Frame(Frame),
+    // Meanings found for a word, to choose the video to play
+    Found(String, Vec<Meaning>),
+}
+
+// List of the videos of a word, grouped by meaning
+pub struct VideoPicker {
+    pub word: String,
+    pub meanings: Vec<Meaning>,
+    // Index of the highlighted video, across meanings
+    pub state: ListState,
+}
+
+impl VideoPicker {
+    pub fn new(word: String, meanings: Vec<Meaning>) -> Self {
+        let mut state = ListState::default();
+        state.select(Some(0));
+        Self {
+            word,
+            meanings,
+            state,
+        }
+    }
+
+    fn len(&self) -> usize {
+        all_videos(&self.meanings).len()
+    }
+
+    pub fn down(&mut self) {
+        let i = self.state.selected().unwrap_or(0);
+        self.state.select(Some((i + 1) % self.len()));
+    }
+
+    pub fn up(&mut self) {
+        let i = self.state.selected().unwrap_or(0);
+        self.state.select(Some((self.len() + i - 1) % self.len()));
+    }
+
+    pub fn selected(&self) -> &Video {
+        all_videos(&self.meanings)[self.state.selected().unwrap_or(0)]
+    }
+}
+
+// Size of the frames decoded for the terminal, the video is scaled
+// and bordered to keep its aspect ratio
+const FRAME_WIDTH: usize = 160;
+const FRAME_HEIGHT: usize = 120;
+
+// Speeds of the playback, slowest first
+pub const RATES: [f64; 3] = [0.25, 0.5, 1.0];
+
+// Command of the learner during the playback
+pub enum Control {
+    Rate(f64),
+    Pause(bool),
+    Loop(bool),
+    // Show the next frame, the video is paused
+    Step,
+    Stop,
+}
+
+// Settings of the playback chosen by the learner
+#[derive(Clone, Copy)]
+pub struct Playback {
+    pub rate: f64,
+    pub looping: bool,
+    pub paused: bool,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            rate: 1.0,
+            looping: false,
+            paused: false,
+        }
+    }
+}
+
+// Videos of a word, progress of their lookup and control of their playback.
+// It is used by tab 'Dictionary' and by the command line mode.
+pub struct VideoPlayer {
+    source: Arc<dyn VideoSource>,
+    // Offline copy of the videos, shared by every lookup
+    cache: Option<Arc<Mutex<VideoCache>>>,
+    pub output: VideoOutput,
+    pub status: VideoStatus,
+    pub frame: Option<Frame>,
+    // The screen must be drawn again entirely to remove the last frame
+    pub redraw: bool,
+    // Opened once the videos of a word are found
+    pub picker: Option<VideoPicker>,
+    pub playback: Playback,
+    controls: Option<mpsc::Sender<Control>>,
+    // Number of the last lookup, messages of the previous ones are ignored
+    lookup: u64,
+    // The word is not shown, for the recognition quiz of tab 'Learn'
+    pub hidden: bool,
+}
+
+impl VideoPlayer {
+    pub fn new(
+        source: Arc<dyn VideoSource>,
+        cache: Option<Arc<Mutex<VideoCache>>>,
+        output: VideoOutput,
+    ) -> Self {
+        Self {
+            source,
+            cache,
+            output,
+            status: VideoStatus::Idle,
+            frame: None,
+            redraw: false,
+            picker: None,
+            playback: Playback::default(),
+            controls: None,
+            lookup: 0,
+            hidden: false,
+        }
+    }
+
+    // Protocol of the frames drawn in the terminal
+    pub fn graphics(&self) -> Option<Graphics> {
+        match self.output {
+            VideoOutput::Window => None,
+            VideoOutput::Terminal(graphics) => Some(graphics),
+        }
+    }
+
+    // Look for the videos of a word, unless a lookup is already running
+    pub fn search(&mut self, word: &Word, tx: mpsc::Sender<Event<Key>>) {
+        if self.status.is_busy() {
+            return;
+        }
+        self.start_search(word, tx);
+    }
+
+    // Play the video of a word in a loop without showing the word,
+    // the current video is stopped
+    pub fn quiz(&mut self, word: &Word, tx: mpsc::Sender<Event<Key>>) {
+        self.stop();
+        self.hidden = true;
+        self.start_search(word, tx);
+    }
+
+    fn start_search(&mut self, word: &Word, tx: mpsc::Sender<Event<Key>>) {
+        self.lookup += 1;
+        self.controls = None;
+        if self.frame.take().is_some() {
+            self.redraw = true;
+        }
+        self.status = VideoStatus::Searching(word.name.to_string());
+        spawn_search_worker(
+            Arc::clone(&self.source),
+            self.cache.clone(),
+            word.clone(),
+            self.lookup,
+            tx,
+        );
+    }
+
+    // Play the video highlighted in the picker and close it
+    pub fn play_selected(&mut self, tx: mpsc::Sender<Event<Key>>) {
+        if let Some(picker) = self.picker.take() {
+            let uri = picker.selected().uri.to_string();
+            let (controls, rx) = mpsc::channel();
+            self.status = VideoStatus::Playing(picker.word.to_string());
+            self.playback = Playback::default();
+            self.controls = Some(controls);
+            let frames = match self.output {
+                VideoOutput::Window => None,
+                VideoOutput::Terminal(_) => Some(tx.clone()),
+            };
+            spawn_play_worker(picker.word, uri, self.lookup, rx, frames, tx);
+        }
     }
 
-    for (i, uri) in urls.iter().enumerate() {
-        println!("[{}] - {}", i, uri);
+    // Handle a message of the workers, a word with a single video
+    // is played without opening the picker. The first video of a
+    // hidden word is played in a loop, the picker would show the word.
+    pub fn handle(&mut self, lookup: u64, event: VideoEvent, tx: mpsc::Sender<Event<Key>>) {
+        if lookup != self.lookup {
+            return;
+        }
+        match event {
+            VideoEvent::Status(status) => {
+                if !status.is_busy() {
+                    self.controls = None;
+                    if self.frame.take().is_some() {
+                        self.redraw = true;
+                    }
+                }
+                self.status = status;
+            }
+            VideoEvent::Frame(frame) => {
+                if self.controls.is_some() {
+                    self.frame = Some(frame);
+                }
+            }
+            VideoEvent::Found(word, meanings) => {
+                let picker = VideoPicker::new(word, meanings);
+                let single = picker.len() == 1;
+                self.status = VideoStatus::Idle;
+                self.picker = Some(picker);
+                if self.hidden {
+                    self.play_selected(tx);
+                    self.playback.looping = true;
+                    self.control(Control::Loop(true));
+                } else if single {
+                    self.play_selected(tx);
+                }
+            }
+        }
+    }
+
+    // Stop the video and close the picker. A lookup still running
+    // is forgotten, its result would show the word or play its video.
+    pub fn stop(&mut self) {
+        self.picker = None;
+        self.hidden = false;
+        self.control(Control::Stop);
+        self.controls = None;
+        self.lookup += 1;
+        self.status = VideoStatus::Idle;
+        if self.frame.take().is_some() {
+            self.redraw = true;
+        }
+    }
+
+    fn control(&self, control: Control) {
+        if let Some(controls) = &self.controls {
+            controls.send(control).unwrap_or_default();
+        }
+    }
+
+    // Keys of the picker and of the playback.
+    // Return false if the key is not used by the player.
+    pub fn input(&mut self, key: Key, tx: mpsc::Sender<Event<Key>>) -> bool {
+        // Choose a video in the picker while it is opened
+        if let Some(picker) = self.picker.as_mut() {
+            match key {
+                Key::Char('j') | Key::Down => picker.down(),
+                Key::Char('k') | Key::Up => picker.up(),
+                Key::Char('\n') => self.play_selected(tx),
+                Key::Char('q') | Key::Esc => self.picker = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        if !matches!(self.status, VideoStatus::Playing(_)) {
+            return false;
+        }
+        let rate = RATES.iter().position(|r| *r == self.playback.rate).unwrap_or(2);
+        match key {
+            Key::Char(' ') => {
+                self.playback.paused = !self.playback.paused;
+                self.control(Control::Pause(self.playback.paused));
+            }
+            // Slower and faster
+            Key::Char('-') => {
+                self.playback.rate = RATES[rate.saturating_sub(1)];
+                self.control(Control::Rate(self.playback.rate));
+            }
+            Key::Char('+') | Key::Char('=') => {
+                self.playback.rate = RATES[(rate + 1).min(RATES.len() - 1)];
+                self.control(Control::Rate(self.playback.rate));
+            }
+            Key::Char('o') => {
+                self.playback.looping = !self.playback.looping;
+                self.control(Control::Loop(self.playback.looping));
+            }
+            Key::Char('.') => {
+                self.playback.paused = true;
+                self.control(Control::Step);
+            }
+            Key::Char('x') | Key::Esc => self.control(Control::Stop),
+            _ => return false,
+        }
+        true
+    }
+}
+
+// Look for the videos of a word in another thread,
+// the result is sent through the events channel
+fn spawn_search_worker(
+    source: Arc<dyn VideoSource>,
+    cache: Option<Arc<Mutex<VideoCache>>>,
+    word: Word,
+    lookup: u64,
+    tx: mpsc::Sender<Event<Key>>,
+) {
+    thread::spawn(move || {
+        let meanings = find_meanings(source.as_ref(), cache.as_deref(), &word);
+        let word = word.name;
+        let event = match meanings {
+            Ok(meanings) if meanings.is_empty() => VideoEvent::Status(VideoStatus::NotFound(word)),
+            Ok(meanings) => VideoEvent::Found(word, meanings),
+            Err(e) => VideoEvent::Status(VideoStatus::Failed(word, e.to_string())),
+        };
+        tx.send(Event::Video(lookup, event)).unwrap_or_default();
+    });
+}
+
+// Play a video in another thread, its end is sent through the events channel
+fn spawn_play_worker(
+    word: String,
+    uri: String,
+    lookup: u64,
+    controls: mpsc::Receiver<Control>,
+    frames: Option<mpsc::Sender<Event<Key>>>,
+    tx: mpsc::Sender<Event<Key>>,
+) {
+    thread::spawn(move || {
+        let frames = frames.map(|tx| (lookup, tx));
+        let status = match play_video(&uri, controls, frames) {
+            Ok(()) => VideoStatus::Idle,
+            Err(e) => VideoStatus::Failed(word, e.to_string()),
+        };
+        tx.send(Event::Video(lookup, VideoEvent::Status(status)))
+            .unwrap_or_default();
+    });
+}
+
+// Look for the videos of a word, let the user choose one in the terminal
+// and play it with the same keys as in the TUI
+pub fn video_routine(mut player: VideoPlayer, word: Word) -> Result<(), io::Error> {
+    let stdout = io::stdout().into_raw_mode()?;
+    let mut terminal = Terminal::new(TermionBackend::new(stdout))?;
+    terminal.clear()?;
+    terminal.hide_cursor()?;
+
+    let events = Events::new(200);
+    player.search(&word, events.tx.clone());
+
+    // Stop once the lookup or the video is over, or the picker closed
+    while player.status.is_busy() || player.picker.is_some() {
+        if player.redraw {
+            clear_image(terminal.backend_mut(), player.graphics())?;
+            terminal.clear()?;
+            player.redraw = false;
+        }
+        draw_video_player(&mut terminal, &mut player);
+        match events.rx.recv() {
+            Ok(Event::Input(key)) => {
+                if !player.input(key, events.tx.clone()) && key == Key::Char('q') {
+                    break;
+                }
+            }
+            Ok(Event::Video(lookup, message)) => player.handle(lookup, message, events.tx.clone()),
+            Ok(Event::Tick) => {}
+            Err(_) => break,
+        }
     }
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).expect("Did not enter a correct string");
-    let input_num: usize = match input.trim().parse::<usize>() {
-        Ok(n) => n,
-        Err(e) => {
-           println!("{}", e);
-           return Ok(())
+    clear_image(terminal.backend_mut(), player.graphics())?;
+    terminal.clear()?;
+    terminal.show_cursor()?;
+    drop(terminal);
+
+    match player.status {
+        VideoStatus::NotFound(word) => println!("No video found for {}", word),
+        VideoStatus::Failed(word, e) => {
+            eprintln!("error: {}: {}", word, e);
+            process::exit(1);
         }
-    };
-    if input_num >= urls.len() {
-        println!("Input invalid");
-        return Ok(())
+        _ => {}
     }
+    Ok(())
+}
 
-    play_video(&urls[input_num])
+// Jump to a position of the video and play it from there at the given rate
+fn seek(playbin: &gstreamer::Element, rate: f64, position: gstreamer::ClockTime) -> Result<(), VideoError> {
+    playbin
+        .seek(
+            rate,
+            gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
+            gstreamer::SeekType::Set,
+            position,
+            gstreamer::SeekType::End,
+            gstreamer::ClockTime::from_seconds(0),
+        )
+        .map_err(playback_error)
+}
+
+// Show the next frame of a paused video
+fn step(playbin: &gstreamer::Element) -> Result<(), VideoError> {
+    let sink = playbin
+        .get_property("video-sink")
+        .map_err(playback_error)?
+        .get::<gstreamer::Element>()
+        .map_err(playback_error)?;
+    if let Some(sink) = sink {
+        let event = gstreamer::event::Step::new(gstreamer::format::Buffers(Some(1)), 1.0, true, false);
+        sink.send_event(event);
+    }
+    Ok(())
+}
+
+// Apply a command of the learner, return false to stop the playback
+fn apply(playbin: &gstreamer::Element, control: Control, playback: &mut Playback) -> Result<bool, VideoError> {
+    match control {
+        Control::Rate(rate) => {
+            playback.rate = rate;
+            let position = playbin
+                .query_position::<gstreamer::ClockTime>()
+                .unwrap_or_else(|| gstreamer::ClockTime::from_seconds(0));
+            seek(playbin, rate, position)?;
+        }
+        Control::Pause(paused) => {
+            playback.paused = paused;
+            let state = if paused {
+                gstreamer::State::Paused
+            } else {
+                gstreamer::State::Playing
+            };
+            playbin.set_state(state).map_err(playback_error)?;
+        }
+        Control::Loop(looping) => playback.looping = looping,
+        Control::Step => {
+            if !playback.paused {
+                playback.paused = true;
+                playbin
+                    .set_state(gstreamer::State::Paused)
+                    .map_err(playback_error)?;
+            }
+            step(playbin)?;
+        }
+        Control::Stop => return Ok(false),
+    }
+    Ok(true)
 }
 
-pub fn query_videos(word: &str) -> Vec<String> {
-    let link = format!("https://dico.elix-lsf.fr/dictionnaire/{}", word);
-    let body = reqwest::blocking::get(&link).unwrap().text().unwrap();
+// Frame of a sample decoded by the appsink
+fn sample_frame(sample: &gstreamer::Sample) -> Option<Frame> {
+    let structure = sample.get_caps()?.get_structure(0)?;
+    let width = structure.get_some::<i32>("width").ok()? as usize;
+    let height = structure.get_some::<i32>("height").ok()? as usize;
+    let buffer = sample.get_buffer()?.map_readable().ok()?;
+    let data = buffer.as_slice().to_vec();
 
-    let mut videos = Vec::new();
+    let stride = data.len() / height.max(1);
+    if stride < width * 3 {
+        return None;
+    }
+    Some(Frame {
+        width,
+        height,
+        stride,
+        data,
+    })
+}
 
-    let req = Regex::new("<video src=\"(https://www.elix-lsf.fr/.+?mp4)\"></video>").unwrap();
-    for cap in req.captures_iter(&body) {
-        videos.push(String::from(&cap[1]));
-    };
+// Sink of playbin sending the decoded frames through the events channel,
+// with the number of the lookup
+fn frame_sink(lookup: u64, tx: mpsc::Sender<Event<Key>>) -> Result<gstreamer::Element, VideoError> {
+    let bin = gstreamer::parse_bin_from_description(
+        &format!(
+            "videoconvert ! videoscale ! video/x-raw,format=RGB,width={},height={} \
+             ! appsink name=frames emit-signals=true max-buffers=1 drop=true",
+            FRAME_WIDTH, FRAME_HEIGHT
+        ),
+        true,
+    )
+    .map_err(playback_error)?;
+    let appsink = bin
+        .get_by_name("frames")
+        .ok_or_else(|| playback_error("pipeline without appsink"))?;
 
-    videos
+    appsink
+        .connect("new-sample", false, move |args| {
+            let sample = args[0]
+                .get::<gstreamer::Element>()
+                .ok()
+                .flatten()
+                .and_then(|appsink| appsink.emit("pull-sample", &[]).ok().flatten())
+                .and_then(|sample| sample.get::<gstreamer::Sample>().ok().flatten());
+            if let Some(frame) = sample.as_ref().and_then(sample_frame) {
+                tx.send(Event::Video(lookup, VideoEvent::Frame(frame)))
+                    .unwrap_or_default();
+            }
+            Some(gstreamer::FlowReturn::Ok.to_value())
+        })
+        .map_err(playback_error)?;
+
+    Ok(bin.upcast())
 }
 
-pub fn play_video(uri: &str) -> Result<(), io::Error> {
-    gstreamer::init().unwrap();
-    let playbin = gstreamer::ElementFactory::make("playbin", None).unwrap();
-    playbin.set_property("uri", &uri).unwrap();
-    let bus = playbin.get_bus().unwrap();
+// Play a video until its end, or forever when it loops, following
+// the commands of the learner. The video is played in a window,
+// or its frames are sent through the events channel with the number of the lookup.
+pub fn play_video(
+    uri: &str,
+    controls: mpsc::Receiver<Control>,
+    frames: Option<(u64, mpsc::Sender<Event<Key>>)>,
+) -> Result<(), VideoError> {
+    gstreamer::init().map_err(playback_error)?;
+    let playbin = gstreamer::ElementFactory::make("playbin", None).map_err(playback_error)?;
+    playbin.set_property("uri", &uri).map_err(playback_error)?;
+    if let Some((lookup, tx)) = frames {
+        playbin
+            .set_property("video-sink", &frame_sink(lookup, tx)?)
+            .map_err(playback_error)?;
+    }
+    let bus = playbin
+        .get_bus()
+        .ok_or_else(|| playback_error("pipeline without bus"))?;
     playbin
         .set_state(gstreamer::State::Playing)
-        .expect("Unable to set the pipeline to the 'Playing' state");
+        .map_err(playback_error)?;
+
+    let mut playback = Playback::default();
+    let mut result = Ok(());
+    'playback: loop {
+        for control in controls.try_iter() {
+            match apply(&playbin, control, &mut playback) {
+                Ok(true) => {}
+                Ok(false) => break 'playback,
+                Err(e) => {
+                    result = Err(e);
+                    break 'playback;
+                }
+            }
+        }
+
+        // Wait a little for a message, to check the commands again
+        let msg = match bus.timed_pop(gstreamer::ClockTime::from_mseconds(50)) {
+            Some(msg) => msg,
+            None => continue,
+        };
 
-    for msg in bus.iter_timed(gstreamer::CLOCK_TIME_NONE) {
         use gstreamer::MessageView;
 
         match msg.view() {
+            // Start again from the beginning when the video loops
+            MessageView::Eos(..) if playback.looping => {
+                if let Err(e) = seek(&playbin, playback.rate, gstreamer::ClockTime::from_seconds(0)) {
+                    result = Err(e);
+                    break;
+                }
+            }
             MessageView::Eos(..) => break,
-            /*
             MessageView::Error(err) => {
-                println!(
-                    "Error from {:?}: {} ({:?})",
-                    err.get_src().map(|s| s.get_path_string()),
-                    err.get_error(),
-                    err.get_debug()
-                );
+                result = Err(playback_error(err.get_error()));
                 break;
             }
-            */
             MessageView::StateChanged(state_changed) =>
             // We are only interested in state-changed messages from playbin
             {
@@ -80,8 +655,9 @@ pub fn play_video(uri: &str) -> Result<(), io::Error> {
                     && state_changed.get_current() == gstreamer::State::Playing
                 {
                     // Generate a dot graph of the pipeline to GST_DEBUG_DUMP_DOT_DIR if defined
-                    let bin_ref = playbin.downcast_ref::<gstreamer::Bin>().unwrap();
-                    bin_ref.debug_to_dot_file(gstreamer::DebugGraphDetails::all(), "PLAYING");
+                    if let Some(bin_ref) = playbin.downcast_ref::<gstreamer::Bin>() {
+                        bin_ref.debug_to_dot_file(gstreamer::DebugGraphDetails::all(), "PLAYING");
+                    }
                 }
             }
 
@@ -89,5 +665,9 @@ pub fn play_video(uri: &str) -> Result<(), io::Error> {
         }
     }
 
-    Ok(())
+    // Release the pipeline so that another video can be played
+    playbin
+        .set_state(gstreamer::State::Null)
+        .map_err(playback_error)?;
+    result
 }
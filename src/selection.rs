@@ -1,5 +1,7 @@
 use tui::widgets::ListState;
 
+use crate::search::Search;
+
 // This structure holds the menu navigation information
 pub struct Selection {
     pub categorie_state: ListState,
@@ -9,7 +11,11 @@ pub struct Selection {
     focus_num: usize,
     words_len: usize,
     cat_len: usize,
+    // Index of the grouping of words displayed as categories
+    grouping: usize,
     done: bool,
+    // Search prompt, if opened
+    pub search: Option<Search>,
 }
 
 impl Selection {
@@ -22,7 +28,9 @@ impl Selection {
             focus_num: 0,
             words_len: 0,
             cat_len: size,
+            grouping: 0,
             done: false,
+            search: None,
         };
         res.categorie_state.select(Some(0));
         res
@@ -72,10 +80,6 @@ impl Selection {
         }
     }
 
-    pub fn reset_word_index(&mut self) {
-        self.word_num = 0;
-    }
-
     // Getter for actual categorie index
     pub fn get_categorie_index(&self) -> usize {
         self.cat_num
@@ -86,6 +90,34 @@ impl Selection {
         self.word_num
     }
 
+    // Display categories of another grouping
+    pub fn set_grouping(&mut self, index: usize, size: usize) {
+        self.grouping = index;
+        self.cat_len = size;
+        self.cat_num = 0;
+        self.categorie_state.select(Some(0));
+        self.focus_left();
+    }
+
+    pub fn get_grouping_index(&self) -> usize {
+        self.grouping
+    }
+
+    // Select a word of a categorie and focus on it
+    pub fn jump(&mut self, cat: usize, word: usize, w_size: usize) {
+        self.cat_num = cat;
+        self.categorie_state.select(Some(cat));
+        self.word_num = word;
+        self.focus_right(w_size);
+    }
+
+    // Go back to the first word of a new list of words
+    pub fn restart(&mut self, w_size: usize) {
+        self.word_num = 0;
+        self.done = false;
+        self.focus_right(w_size);
+    }
+
     pub fn reset(&mut self) {
         self.cat_num = 0;
         self.word_num = 0;
@@ -0,0 +1,552 @@
+use rand::prelude::*;
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use yaml_rust::yaml::Hash;
+use yaml_rust::Yaml;
+
+use crate::draw::WordState;
+use crate::loader::{Categorie, Word};
+use crate::numbers::{NumberDeck, NUMBERS_DECK};
+use crate::quiz::{choices, Answer, LearnMode};
+use crate::scheduler::{CardDirection, Grade, Scheduler};
+use crate::selection::Selection;
+use crate::setup::SessionSetup;
+use crate::high_scores::Score;
+use crate::storage::{data_dir, load_yaml, now, save_yaml, today};
+
+// This structure holds the words of a session in tab 'Learn'
+// and the progression of the learner. Failed words are asked again
+// in new rounds until every word is validated.
+pub struct Session<'a> {
+    // Words of the current round
+    pub words: Vec<(&'a Word, WordState)>,
+    // Rounds already done, the first one holds every word of the session
+    rounds: Vec<Vec<(&'a Word, WordState)>>,
+    // Every categorie, where the words of the sessions and the
+    // distractors of the multiple choice quiz are drawn
+    pub categories: &'a [Categorie],
+    // Numbers drawn instead of the words of the decks
+    numbers: &'a NumberDeck,
+    // Options of the next session, and whether they are being chosen
+    pub setup: SessionSetup,
+    pub configuring: bool,
+    pub help: bool,
+    pub mode: LearnMode,
+    // Side of the cards shown when recalling words
+    pub direction: CardDirection,
+    // Answer of the recognition quiz and of typed mode
+    pub answer: Answer,
+    // Countdown of each word of a timed challenge, the name of its
+    // high scores table and the rank of its score once it is over
+    pub limit: Option<Duration>,
+    pub challenge: String,
+    pub rank: Option<usize>,
+    // Beginning of the time counted since the last pause, and time before it
+    begin: Instant,
+    elapsed: Duration,
+    paused: bool,
+    time: Duration,
+}
+
+fn is_current(state: &WordState) -> bool {
+    matches!(state, WordState::Current | WordState::Peeked)
+}
+
+// File keeping a session interrupted by quitting, to resume it on the next launch
+fn saved_path() -> PathBuf {
+    data_dir().join("session.yaml")
+}
+
+fn word_to_yaml((word, state): &(&Word, WordState)) -> Yaml {
+    let mut h = Hash::new();
+    h.insert(Yaml::from_str("word"), Yaml::String(word.name.to_string()));
+    h.insert(Yaml::from_str("deck"), Yaml::String(word.deck.to_string()));
+    let name = match state {
+        WordState::Graded(grade, time, spent) => {
+            h.insert(Yaml::from_str("grade"), Yaml::from_str(grade.name()));
+            h.insert(Yaml::from_str("time"), Yaml::Integer(*time as i64));
+            h.insert(
+                Yaml::from_str("spent"),
+                Yaml::Integer(spent.as_millis() as i64),
+            );
+            "graded"
+        }
+        WordState::Skipped(spent) => {
+            h.insert(
+                Yaml::from_str("spent"),
+                Yaml::Integer(spent.as_millis() as i64),
+            );
+            "skipped"
+        }
+        WordState::Current => "current",
+        WordState::Peeked => "peeked",
+        WordState::Next => "next",
+    };
+    h.insert(Yaml::from_str("state"), Yaml::from_str(name));
+    Yaml::Hash(h)
+}
+
+// Words unknown to the decks, which may have changed, are dropped
+fn word_from_yaml<'a>(
+    node: &Yaml,
+    categories: &'a [Categorie],
+    numbers: &'a NumberDeck,
+) -> Option<(&'a Word, WordState)> {
+    let name = node["word"].as_str()?;
+    let deck = node["deck"].as_str()?;
+    let word = if deck == NUMBERS_DECK {
+        numbers.get(name.parse().ok()?)?
+    } else {
+        categories
+            .iter()
+            .flat_map(|categorie| categorie.words.iter())
+            .find(|word| word.deck == deck && word.name == name)?
+    };
+    let state = match node["state"].as_str()? {
+        "graded" => WordState::Graded(
+            Grade::from_name(node["grade"].as_str()?)?,
+            node["time"].as_i64()? as u64,
+            Duration::from_millis(node["spent"].as_i64()? as u64),
+        ),
+        "skipped" => {
+            WordState::Skipped(Duration::from_millis(node["spent"].as_i64()? as u64))
+        }
+        "current" => WordState::Current,
+        "peeked" => WordState::Peeked,
+        "next" => WordState::Next,
+        _ => return None,
+    };
+    Some((word, state))
+}
+
+impl<'a> Session<'a> {
+    pub fn new(categories: &'a [Categorie], numbers: &'a NumberDeck) -> Self {
+        Self {
+            words: vec![],
+            rounds: vec![],
+            categories,
+            numbers,
+            setup: SessionSetup::new(categories.len()),
+            configuring: false,
+            help: false,
+            mode: LearnMode::Recall,
+            direction: CardDirection::Forward,
+            answer: Answer::new(),
+            limit: None,
+            challenge: String::new(),
+            rank: None,
+            begin: Instant::now(),
+            elapsed: Duration::new(0, 0),
+            paused: false,
+            time: Duration::new(0, 0),
+        }
+    }
+
+    // Save the session to resume it on the next launch,
+    // or remove the saved one if the session is over
+    pub fn save(&self) -> Result<(), io::Error> {
+        if !self.in_progress() {
+            return match fs::remove_file(saved_path()) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+
+        let rounds = self
+            .rounds
+            .iter()
+            .chain(std::iter::once(&self.words))
+            .map(|round| Yaml::Array(round.iter().map(word_to_yaml).collect()))
+            .collect();
+        let mut doc = Hash::new();
+        doc.insert(Yaml::from_str("mode"), Yaml::from_str(self.mode.name()));
+        doc.insert(
+            Yaml::from_str("direction"),
+            Yaml::from_str(self.direction.name()),
+        );
+        doc.insert(
+            Yaml::from_str("elapsed"),
+            Yaml::Integer(self.elapsed_now().as_millis() as i64),
+        );
+        if let Some(limit) = self.limit {
+            doc.insert(
+                Yaml::from_str("limit"),
+                Yaml::Integer(limit.as_millis() as i64),
+            );
+            doc.insert(
+                Yaml::from_str("challenge"),
+                Yaml::String(self.challenge.to_string()),
+            );
+        }
+        doc.insert(Yaml::from_str("rounds"), Yaml::Array(rounds));
+        save_yaml(&saved_path(), &Yaml::Hash(doc))
+    }
+
+    // Load the session saved when quitting, paused
+    pub fn load(&mut self) -> Result<(), io::Error> {
+        let doc = match load_yaml(&saved_path())? {
+            Some(doc) => doc,
+            None => return Ok(()),
+        };
+        let mut rounds: Vec<Vec<(&'a Word, WordState)>> = doc["rounds"]
+            .as_vec()
+            .map(|rounds| {
+                rounds
+                    .iter()
+                    .filter_map(|round| {
+                        let words: Vec<_> = round
+                            .as_vec()?
+                            .iter()
+                            .filter_map(|node| word_from_yaml(node, self.categories, self.numbers))
+                            .collect();
+                        Some(words).filter(|words| !words.is_empty())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // The current word may be missing from the decks
+        let words = match rounds.pop() {
+            Some(words) if words.iter().any(|(_, state)| is_current(state)) => words,
+            _ => return Ok(()),
+        };
+        self.words = words;
+        self.rounds = rounds;
+        self.mode = doc["mode"]
+            .as_str()
+            .and_then(LearnMode::from_name)
+            .unwrap_or(LearnMode::Recall);
+        self.direction = doc["direction"]
+            .as_str()
+            .and_then(CardDirection::from_name)
+            .unwrap_or(CardDirection::Forward);
+        self.limit = doc["limit"]
+            .as_i64()
+            .map(|limit| Duration::from_millis(limit as u64));
+        self.challenge = doc["challenge"].as_str().unwrap_or_default().to_string();
+        self.elapsed = Duration::from_millis(doc["elapsed"].as_i64().unwrap_or(0) as u64);
+        self.time = self.elapsed;
+        self.paused = true;
+        Ok(())
+    }
+
+    // A session is in progress until every word is validated
+    pub fn in_progress(&self) -> bool {
+        self.words.iter().any(|(_, state)| is_current(state))
+    }
+
+    // Point the selection at the current word again, once
+    // it has been used by another tab
+    pub fn resume_selection(&mut self, states: &mut Selection) {
+        let len = self.words.len();
+        states.restart(len);
+        match self.words.iter().position(|(_, state)| is_current(state)) {
+            Some(index) => {
+                states.jump(states.get_categorie_index(), index, len);
+                self.draw_choices(states);
+            }
+            None => states.set_done(),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // Freeze the time of the session
+    pub fn pause(&mut self) {
+        if !self.paused {
+            self.elapsed = self.elapsed_now();
+            self.paused = true;
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.paused {
+            self.begin = Instant::now();
+            self.paused = false;
+        }
+    }
+
+    // Time spent on the current word, what remains once
+    // the time of the previous words is removed
+    fn spent_current(&self) -> Duration {
+        let previous = self
+            .rounds
+            .iter()
+            .flatten()
+            .chain(self.words.iter())
+            .filter_map(|(_, state)| state.spent())
+            .sum::<Duration>();
+        self.elapsed_now().checked_sub(previous).unwrap_or_default()
+    }
+
+    // Time left to answer the current word of a challenge
+    pub fn remaining(&self, states: &Selection) -> Option<Duration> {
+        match self.limit {
+            Some(limit) if !states.is_done() && self.in_progress() => {
+                Some(limit.checked_sub(self.spent_current()).unwrap_or_default())
+            }
+            _ => None,
+        }
+    }
+
+    // Score of the challenge, from the words answered right
+    pub fn score(&self) -> Score {
+        let results = self.results();
+        Score {
+            correct: results
+                .iter()
+                .filter(|(_, state)| !state.is_failed() && !state.is_skipped())
+                .count(),
+            words: results.iter().filter(|(_, state)| !state.is_skipped()).count(),
+            time: self.elapsed_now(),
+            day: today(),
+        }
+    }
+
+    // Time of the session without its pauses
+    fn elapsed_now(&self) -> Duration {
+        if self.paused {
+            self.elapsed
+        } else {
+            self.elapsed + self.begin.elapsed()
+        }
+    }
+
+    // Start a session on the given words, in their order
+    pub fn start(&mut self, words: Vec<&'a Word>, states: &mut Selection) {
+        self.words = words
+            .into_iter()
+            .map(|word| (word, WordState::Next))
+            .collect();
+        self.rounds.clear();
+        self.restart(states);
+    }
+
+    // Open the setup pop-up of the next session, the highlighted
+    // categorie is chosen when no categorie is
+    pub fn open_setup(&mut self, highlighted: Option<usize>) {
+        if let Some(index) = highlighted {
+            if !self.setup.chosen.contains(&true) {
+                self.setup.chosen[index] = true;
+            }
+        }
+        self.setup.message = None;
+        self.configuring = true;
+    }
+
+    // Start a session on the words chosen in the setup pop-up.
+    // Return false, and keep the pop-up opened, if no word matches.
+    pub fn start_setup(&mut self, scheduler: &Scheduler, states: &mut Selection) -> bool {
+        let direction = self.review_direction();
+        let words = self.setup.words(self.categories, self.numbers, scheduler, direction);
+        if words.is_empty() {
+            return false;
+        }
+        self.configuring = false;
+        self.limit = self.setup.limit();
+        self.challenge = self.setup.challenge(self.categories);
+        self.start(words, states);
+        true
+    }
+
+    // Every word of the session, in the order of the first round
+    fn restore_words(&mut self) {
+        if !self.rounds.is_empty() {
+            self.words = self.rounds[0]
+                .iter()
+                .map(|(word, _)| (*word, WordState::Next))
+                .collect();
+            self.rounds.clear();
+        }
+    }
+
+    // Start again the session from its first word
+    fn restart(&mut self, states: &mut Selection) {
+        self.restore_words();
+        for (_, state) in self.words.iter_mut() {
+            *state = WordState::Next;
+        }
+        self.words[0].1 = WordState::Current;
+
+        // Dirty hacks
+        states.restart(self.words.len());
+
+        self.begin = Instant::now();
+        self.elapsed = Duration::new(0, 0);
+        self.paused = false;
+        self.time = Duration::new(0, 0);
+        self.rank = None;
+        self.help = false;
+        self.answer.reset();
+        self.draw_choices(states);
+    }
+
+    // Number of the current round, from 1
+    pub fn round(&self) -> usize {
+        self.rounds.len() + 1
+    }
+
+    // Answers of every word of the session, those of the first round
+    pub fn results(&self) -> &[(&'a Word, WordState)] {
+        self.rounds.first().unwrap_or(&self.words)
+    }
+
+    // Time since the beginning of the session, frozen once it is done
+    pub fn time(&self) -> &Duration {
+        &self.time
+    }
+
+    pub fn update_time(&mut self, states: &Selection) {
+        if !states.is_done() {
+            self.time = self.elapsed_now();
+        }
+    }
+
+    // Mark the current word as helped, without displaying its help
+    pub fn peek(&mut self, states: &Selection) {
+        if !states.is_done() {
+            self.words[states.get_word_index()].1 = WordState::Peeked;
+        }
+    }
+
+    // Switch between recalling the sign of a word, recognising
+    // a sign and typing a word
+    pub fn set_mode(&mut self, mode: LearnMode, states: &Selection) {
+        self.mode = mode;
+        self.answer.reset();
+        self.draw_choices(states);
+    }
+
+    // Candidate words of the current word in the multiple choice quiz
+    fn draw_choices(&mut self, states: &Selection) {
+        if self.mode == LearnMode::Choice && !states.is_done() {
+            let word = self.words[states.get_word_index()].0;
+            self.answer.choices = if word.deck == NUMBERS_DECK {
+                self.numbers.choices(word)
+            } else {
+                choices(word, self.categories)
+            };
+        }
+    }
+
+    // Direction in which the answers are scheduled, recognising a sign,
+    // typing or picking a word goes from the sign to the word
+    pub fn review_direction(&self) -> CardDirection {
+        match self.mode {
+            LearnMode::Recall => self.direction,
+            LearnMode::Recognition | LearnMode::Typed | LearnMode::Choice => CardDirection::Reverse,
+        }
+    }
+
+    // Show the description instead of the word, or the other way round.
+    // The answers already given are kept, the current word is asked
+    // again in the new direction with its help hidden.
+    pub fn toggle_direction(&mut self) {
+        self.direction = match self.direction {
+            CardDirection::Forward => CardDirection::Reverse,
+            CardDirection::Reverse => CardDirection::Forward,
+        };
+        self.help = false;
+    }
+
+    // Display the help of the current word
+    pub fn toggle_help(&mut self, states: &Selection) {
+        self.peek(states);
+        self.help = !self.help;
+    }
+
+    // Grade the current word and go to the next one.
+    // Return the graded word, the time of the grade and the time spent on it.
+    pub fn grade(&mut self, grade: Grade, states: &mut Selection) -> (&'a Word, u64, Duration) {
+        let spent = self.spent_current();
+
+        let (word, state) = &mut self.words[states.get_word_index()];
+        let time = now();
+        *state = WordState::Graded(grade, time, spent);
+        let word = *word;
+        self.advance(states);
+
+        (word, time, spent)
+    }
+
+    // Leave the current word ungraded and go to the next one,
+    // its sign could not be played by the quiz
+    pub fn skip(&mut self, states: &mut Selection) {
+        if states.is_done() || !self.in_progress() {
+            return;
+        }
+        let spent = self.spent_current();
+        self.words[states.get_word_index()].1 = WordState::Skipped(spent);
+        self.answer.reset();
+        self.advance(states);
+    }
+
+    fn advance(&mut self, states: &mut Selection) {
+        // If the index is over total words in the session
+        if states.get_word_index() < self.words.len() - 1 {
+            states.down();
+            self.help = false;
+            self.words[states.get_word_index()].1 = WordState::Current;
+            self.draw_choices(states);
+        } else if !self.next_round(states) {
+            states.set_done()
+        }
+    }
+
+    // Ask again the failed words of the round, in a new order.
+    // Return false if there is no failed word.
+    fn next_round(&mut self, states: &mut Selection) -> bool {
+        // A challenge is over after a single round
+        if self.limit.is_some() {
+            return false;
+        }
+        let mut failed: Vec<(&'a Word, WordState)> = self
+            .words
+            .iter()
+            .filter(|(_, state)| state.is_failed())
+            .map(|(word, _)| (*word, WordState::Next))
+            .collect();
+        if failed.is_empty() {
+            return false;
+        }
+        failed.shuffle(&mut rand::thread_rng());
+        failed[0].1 = WordState::Current;
+        let round = std::mem::replace(&mut self.words, failed);
+        self.rounds.push(round);
+
+        states.restart(self.words.len());
+        self.help = false;
+        self.draw_choices(states);
+        true
+    }
+
+    // Start a new session with only the words failed in the first round.
+    // Return false if there is no failed word.
+    pub fn retry_failed(&mut self, states: &mut Selection) -> bool {
+        let mut failed: Vec<&'a Word> = self
+            .results()
+            .iter()
+            .filter(|(_, state)| state.is_failed())
+            .map(|(word, _)| *word)
+            .collect();
+        if failed.is_empty() {
+            return false;
+        }
+        failed.shuffle(&mut rand::thread_rng());
+        self.start(failed, states);
+        true
+    }
+
+    // Start again the session in a new order
+    pub fn reshuffle(&mut self, states: &mut Selection) {
+        self.restore_words();
+        self.words.shuffle(&mut rand::thread_rng());
+        self.restart(states);
+    }
+}
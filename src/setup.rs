@@ -0,0 +1,237 @@
+use rand::prelude::*;
+
+use std::time::Duration;
+
+use tui::widgets::ListState;
+
+use crate::loader::{Categorie, Word};
+use crate::numbers::{NumberDeck, BOUNDS};
+use crate::scheduler::{CardDirection, Scheduler};
+
+// Maximum numbers of words of a session, 0 keeps every word
+pub const SIZES: [usize; 5] = [10, 20, 50, 100, 0];
+
+// Countdowns of each word in seconds, 0 when the session is not timed
+pub const TIMERS: [u64; 4] = [0, 5, 10, 20];
+
+// Rows of the setup pop-up before the list of categories
+const OPTION_ROWS: usize = 4;
+
+// Words a session of tab 'Learn' is made of
+#[derive(Clone, Copy, PartialEq)]
+pub enum WordSource {
+    Categories,
+    All,
+    Due,
+    Favourites,
+    // Random numbers generated from the rules of the numbers
+    Numbers,
+}
+
+impl WordSource {
+    const ALL: [WordSource; 5] = [
+        WordSource::Categories,
+        WordSource::All,
+        WordSource::Due,
+        WordSource::Favourites,
+        WordSource::Numbers,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WordSource::Categories => "Chosen categories",
+            WordSource::All => "All words",
+            WordSource::Due => "Words due today",
+            WordSource::Favourites => "Favourite words",
+            WordSource::Numbers => "Random numbers",
+        }
+    }
+}
+
+// Options of the next session, chosen in a pop-up before it starts
+pub struct SessionSetup {
+    pub source: WordSource,
+    // Index in `SIZES`
+    pub size: usize,
+    pub shuffle: bool,
+    // Index in `TIMERS`, a timed session is a challenge with high scores
+    pub timer: usize,
+    // Categories chosen for `WordSource::Categories`
+    pub chosen: Vec<bool>,
+    // Indexes in `BOUNDS` of the smallest and largest numbers
+    // drawn for `WordSource::Numbers`
+    pub smallest: usize,
+    pub largest: usize,
+    // Highlighted row, options first then categories
+    pub state: ListState,
+    // Reason why the session could not start
+    pub message: Option<&'static str>,
+}
+
+impl SessionSetup {
+    pub fn new(categories: usize) -> Self {
+        let mut state = ListState::default();
+        state.select(Some(0));
+        Self {
+            source: WordSource::Due,
+            size: 1,
+            shuffle: true,
+            timer: 0,
+            chosen: vec![false; categories],
+            smallest: 0,
+            largest: 2,
+            state,
+            message: None,
+        }
+    }
+
+    // Number of rows of the pop-up, categories are listed only when
+    // chosen from, and the range of numbers only when drawn from
+    fn rows(&self) -> usize {
+        match self.source {
+            WordSource::Categories => OPTION_ROWS + self.chosen.len(),
+            WordSource::Numbers => OPTION_ROWS + 2,
+            _ => OPTION_ROWS,
+        }
+    }
+
+    fn row(&self) -> usize {
+        self.state.selected().unwrap_or(0)
+    }
+
+    pub fn down(&mut self) {
+        self.state.select(Some((self.row() + 1) % self.rows()));
+    }
+
+    pub fn up(&mut self) {
+        self.state.select(Some((self.rows() + self.row() - 1) % self.rows()));
+    }
+
+    // Change the value of the highlighted option, or choose
+    // the highlighted categorie
+    pub fn change(&mut self, forward: bool) {
+        let step = |index: usize, len: usize| {
+            if forward {
+                (index + 1) % len
+            } else {
+                (len + index - 1) % len
+            }
+        };
+        match self.row() {
+            0 => {
+                let index = WordSource::ALL.iter().position(|s| *s == self.source).unwrap_or(0);
+                self.source = WordSource::ALL[step(index, WordSource::ALL.len())];
+            }
+            1 => self.size = step(self.size, SIZES.len()),
+            2 => self.shuffle = !self.shuffle,
+            3 => self.timer = step(self.timer, TIMERS.len()),
+            // The range holds at least two numbers
+            row if self.source == WordSource::Numbers => {
+                if row == OPTION_ROWS {
+                    self.smallest = step(self.smallest, self.largest);
+                } else {
+                    let above = BOUNDS.len() - self.smallest - 1;
+                    self.largest = self.smallest + 1 + step(self.largest - self.smallest - 1, above);
+                }
+            }
+            row => self.chosen[row - OPTION_ROWS] = !self.chosen[row - OPTION_ROWS],
+        }
+        self.message = None;
+    }
+
+    // Countdown of each word, if the session is a challenge
+    pub fn limit(&self) -> Option<Duration> {
+        match TIMERS[self.timer] {
+            0 => None,
+            seconds => Some(Duration::from_secs(seconds)),
+        }
+    }
+
+    // Name of the high scores table of the challenge: the chosen
+    // categories, or the kind of words, its size and its timer
+    pub fn challenge(&self, categories: &[Categorie]) -> String {
+        let words = match self.source {
+            WordSource::Categories => categories
+                .iter()
+                .zip(self.chosen.iter())
+                .filter(|(_, chosen)| **chosen)
+                .map(|(categorie, _)| categorie.name.as_str())
+                .collect::<Vec<&str>>()
+                .join(", "),
+            WordSource::Numbers => format!(
+                "Numbers {} to {}",
+                BOUNDS[self.smallest], BOUNDS[self.largest]
+            ),
+            source => source.label().to_string(),
+        };
+        let size = match SIZES[self.size] {
+            0 => String::from("every word"),
+            size => format!("{} words", size),
+        };
+        format!("{} - {} - {} s", words, size, TIMERS[self.timer])
+    }
+
+    // Words of the decks matching the source, without duplicates,
+    // in the order of the files
+    fn deck_words<'a>(
+        &self,
+        categories: &'a [Categorie],
+        scheduler: &Scheduler,
+        direction: CardDirection,
+    ) -> Vec<&'a Word> {
+        let mut words: Vec<&Word> = Vec::new();
+        for (i, categorie) in categories.iter().enumerate() {
+            if self.source == WordSource::Categories && !self.chosen[i] {
+                continue;
+            }
+            for word in categorie.words.iter() {
+                let keep = match self.source {
+                    WordSource::Categories | WordSource::All | WordSource::Numbers => true,
+                    WordSource::Due => scheduler.is_due(word, direction),
+                    WordSource::Favourites => scheduler.favourites.contains(word),
+                };
+                if keep && !words.iter().any(|w| w.deck == word.deck && w.name == word.name) {
+                    words.push(word);
+                }
+            }
+        }
+        words
+    }
+
+    // Words of the next session, capped and in the order
+    // of the files or shuffled. Numbers are drawn at random.
+    pub fn words<'a>(
+        &mut self,
+        categories: &'a [Categorie],
+        numbers: &'a NumberDeck,
+        scheduler: &Scheduler,
+        direction: CardDirection,
+    ) -> Vec<&'a Word> {
+        let mut words = match self.source {
+            WordSource::Numbers => {
+                numbers.draw(BOUNDS[self.smallest], BOUNDS[self.largest], SIZES[self.size])
+            }
+            _ => self.deck_words(categories, scheduler, direction),
+        };
+
+        if self.shuffle {
+            words.shuffle(&mut rand::thread_rng());
+        }
+        if SIZES[self.size] > 0 {
+            words.truncate(SIZES[self.size]);
+        }
+
+        if words.is_empty() {
+            self.message = Some(match self.source {
+                WordSource::Categories => "Choose at least one categorie",
+                WordSource::Due => "No word is due today",
+                WordSource::Favourites if scheduler.favourites.is_empty() => {
+                    "No favourite word, mark them with f in tab Dictionary"
+                }
+                WordSource::Numbers => "No number can be composed with the rules",
+                _ => "No word to learn",
+            });
+        }
+        words
+    }
+}
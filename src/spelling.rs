@@ -0,0 +1,182 @@
+use rand::prelude::*;
+
+use std::time::{Duration, Instant};
+
+use crate::loader::{load_file, load_str, Categorie, LoadError, LoadPolicy, Word};
+use crate::search::normalize;
+
+// Alphabet used unless another file is given with --alphabet
+const ALPHABET: &str = include_str!("../alphabet.yaml");
+
+// Names spelled instead of the words of the decks
+const NAMES: [&str; 24] = [
+    "Alice", "Bastien", "Camille", "Chloé", "Damien", "Élodie", "Fatou", "Gaspard",
+    "Hugo", "Inès", "Jules", "Karim", "Léa", "Lucas", "Manon", "Nathan", "Océane",
+    "Pauline", "Quentin", "Sarah", "Théo", "Victor", "Yanis", "Zoé",
+];
+
+// Time each letter is shown while a word is played, slowest first
+pub const SPEEDS: [Duration; 3] = [
+    Duration::from_millis(1500),
+    Duration::from_millis(1000),
+    Duration::from_millis(600),
+];
+
+// Letters of the alphabet, from the given file or the built-in one
+pub fn load_alphabet(
+    file: Option<&str>,
+    policy: LoadPolicy,
+) -> Result<(Vec<Word>, Vec<LoadError>), LoadError> {
+    let (categories, warnings) = match file {
+        Some(file) => load_file(file, policy)?,
+        None => load_str("alphabet.yaml (built-in)", ALPHABET, policy)?,
+    };
+    Ok((categories.into_iter().flat_map(|c| c.words).collect(), warnings))
+}
+
+// Texts spelled in tab 'Alphabet'
+#[derive(Clone, Copy, PartialEq)]
+pub enum SpellingSource {
+    Words,
+    Names,
+}
+
+// Word shown letter by letter with the handshape of each letter.
+// In the spelling quiz the learner spells the word first, then
+// reveals the handshapes one by one to check it.
+pub struct Spelling<'a> {
+    alphabet: &'a [Word],
+    // Words of the decks that can be spelled
+    words: Vec<&'a str>,
+    pub source: SpellingSource,
+    pub text: String,
+    // Letters of the text, without accents, spaces or punctuation
+    pub letters: Vec<char>,
+    // Number of letters whose handshape is shown
+    pub shown: usize,
+    pub quiz: bool,
+    // Letters are shown one after another
+    pub playing: bool,
+    // Index in `SPEEDS`
+    pub speed: usize,
+    last: Instant,
+}
+
+impl<'a> Spelling<'a> {
+    pub fn new(alphabet: &'a [Word], categories: &'a [Categorie]) -> Self {
+        // Only words fully covered by the alphabet are spelled
+        let mut words: Vec<&str> = Vec::new();
+        for word in categories.iter().flat_map(|c| c.words.iter()) {
+            let letters = letters(&word.name);
+            let spellable = !letters.is_empty()
+                && letters.iter().all(|c| find_letter(alphabet, *c).is_some());
+            if spellable && !words.contains(&word.name.as_str()) {
+                words.push(&word.name);
+            }
+        }
+        let mut spelling = Self {
+            alphabet,
+            words,
+            source: SpellingSource::Words,
+            text: String::new(),
+            letters: Vec::new(),
+            shown: 0,
+            quiz: false,
+            playing: false,
+            speed: 1,
+            last: Instant::now(),
+        };
+        spelling.next();
+        spelling
+    }
+
+    // Letter of the alphabet matching a letter of the text
+    pub fn handshape(&self, letter: char) -> Option<&'a Word> {
+        find_letter(self.alphabet, letter)
+    }
+
+    // Draw another word, or another name
+    pub fn next(&mut self) {
+        let mut rng = rand::thread_rng();
+        let text = match self.source {
+            SpellingSource::Words => self.words.choose(&mut rng).copied(),
+            SpellingSource::Names => None,
+        };
+        self.text = text
+            .or_else(|| NAMES.choose(&mut rng).copied())
+            .unwrap_or_default()
+            .to_string();
+        self.letters = letters(&self.text);
+        self.restart();
+    }
+
+    // Show the word again from its first letter, or hide every
+    // handshape in the quiz
+    pub fn restart(&mut self) {
+        self.shown = if self.quiz { 0 } else { 1 };
+        self.playing = false;
+        self.last = Instant::now();
+    }
+
+    pub fn forward(&mut self) {
+        if self.shown < self.letters.len() {
+            self.shown += 1;
+        }
+        self.last = Instant::now();
+    }
+
+    // Hide the last letter shown, the first one stays unless in the quiz
+    pub fn back(&mut self) {
+        if self.shown > usize::from(!self.quiz) {
+            self.shown -= 1;
+        }
+    }
+
+    // Play the letters from the current one, or stop
+    pub fn play(&mut self) {
+        if self.shown == self.letters.len() {
+            self.restart();
+        }
+        self.playing = !self.playing;
+        self.last = Instant::now();
+    }
+
+    // Show the next letter once the current one has been shown long enough
+    pub fn tick(&mut self) {
+        if self.playing && self.last.elapsed() >= SPEEDS[self.speed] {
+            self.forward();
+            self.playing = self.shown < self.letters.len();
+        }
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    pub fn toggle_source(&mut self) {
+        self.source = match self.source {
+            SpellingSource::Words => SpellingSource::Names,
+            SpellingSource::Names => SpellingSource::Words,
+        };
+        self.next();
+    }
+
+    pub fn toggle_quiz(&mut self) {
+        self.quiz = !self.quiz;
+        self.restart();
+    }
+}
+
+fn find_letter(alphabet: &[Word], letter: char) -> Option<&Word> {
+    let letter = letter.to_string();
+    alphabet.iter().find(|word| normalize(&word.name) == letter)
+}
+
+// Letters of a text, in the alphabet of the yaml file
+fn letters(text: &str) -> Vec<char> {
+    normalize(text).chars().filter(|c| !c.is_whitespace()).collect()
+}
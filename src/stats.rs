@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::loader::Categorie;
+use crate::numbers::NUMBERS_DECK;
+use crate::review_log::LogEntry;
+use crate::scheduler::{CardDirection, Grade, Scheduler};
+use crate::storage::today;
+
+// Number of days displayed in the history charts
+pub const HISTORY_DAYS: usize = 30;
+
+// Number of words displayed in the hardest words list
+const HARDEST_WORDS: usize = 10;
+
+// Successful reviews in a row for a word to be considered mastered
+const MASTERED_REPETITIONS: u64 = 2;
+
+// Learning history displayed in tab 'Statistics'
+pub struct Statistics {
+    // Number of reviews for each of the last days, oldest first
+    pub reviews_per_day: Vec<u64>,
+    // Percentage of words not graded 'Again', for each of the
+    // last days with reviews (day index, percentage)
+    pub retention_per_day: Vec<(f64, f64)>,
+    pub total_reviews: usize,
+    pub retention: f64,
+    // Average time taken to answer a word
+    pub average_spent: Duration,
+    // Percentage of mastered words of each categorie, from word to sign
+    pub mastery: Vec<(String, u64)>,
+    // Words failed the most (deck, word, failures, reviews)
+    pub hardest: Vec<(String, String, usize, usize)>,
+}
+
+impl Statistics {
+    pub fn new(scheduler: &Scheduler, categories: &[Categorie]) -> Self {
+        let mastery = categories
+            .iter()
+            .map(|categorie| {
+                let mastered = categorie
+                    .words
+                    .iter()
+                    .filter(|word| {
+                        matches!(scheduler.get(word, CardDirection::Forward),
+                            Some(review) if review.repetitions >= MASTERED_REPETITIONS)
+                    })
+                    .count();
+                let percent = (mastered * 100)
+                    .checked_div(categorie.words.len())
+                    .unwrap_or(0);
+                (categorie.name.to_string(), percent as u64)
+            })
+            .collect();
+
+        Self::from_log(&scheduler.log.entries, today(), mastery)
+    }
+
+    // History of the reviews of a log up to a day, numbers excepted as
+    // they are only drills
+    fn from_log(entries: &[LogEntry], today: u64, mastery: Vec<(String, u64)>) -> Self {
+        let entries: Vec<&LogEntry> = entries
+            .iter()
+            .filter(|entry| entry.deck != NUMBERS_DECK)
+            .collect();
+        let first_day = (today + 1).saturating_sub(HISTORY_DAYS as u64);
+
+        let mut reviews_per_day = vec![0; HISTORY_DAYS];
+        let mut passed_per_day = [0; HISTORY_DAYS];
+        // Counts of each word, by deck and name
+        let mut per_word: HashMap<(&str, &str), (usize, usize)> = HashMap::new();
+        let mut passed = 0;
+
+        for entry in entries.iter() {
+            let failed = entry.grade == Grade::Again;
+            if !failed {
+                passed += 1;
+            }
+
+            let counts = per_word
+                .entry((&entry.deck, &entry.word))
+                .or_insert((0, 0));
+            counts.1 += 1;
+            if failed {
+                counts.0 += 1;
+            }
+
+            let day = entry.time / 86400;
+            if day >= first_day && day <= today {
+                let index = (day - first_day) as usize;
+                reviews_per_day[index] += 1;
+                if !failed {
+                    passed_per_day[index] += 1;
+                }
+            }
+        }
+
+        let retention_per_day = reviews_per_day
+            .iter()
+            .zip(passed_per_day.iter())
+            .enumerate()
+            .filter(|(_, (reviews, _))| **reviews > 0)
+            .map(|(day, (reviews, passed))| (day as f64, *passed as f64 * 100.0 / *reviews as f64))
+            .collect();
+
+        let average_spent = entries.iter().map(|entry| entry.spent).sum::<Duration>()
+            / (entries.len() as u32).max(1);
+
+        let retention = if entries.is_empty() {
+            0.0
+        } else {
+            passed as f64 * 100.0 / entries.len() as f64
+        };
+
+        let mut hardest: Vec<(String, String, usize, usize)> = per_word
+            .into_iter()
+            .filter(|(_, (failures, _))| *failures > 0)
+            .map(|((deck, word), (failures, reviews))| {
+                (deck.to_string(), word.to_string(), failures, reviews)
+            })
+            .collect();
+        // Most failures first, then the lowest success rate
+        hardest.sort_by(|a, b| {
+            b.2.cmp(&a.2)
+                .then((a.3 - a.2).cmp(&(b.3 - b.2)))
+                .then(a.1.cmp(&b.1))
+                .then(a.0.cmp(&b.0))
+        });
+        hardest.truncate(HARDEST_WORDS);
+
+        Self {
+            reviews_per_day,
+            retention_per_day,
+            total_reviews: entries.len(),
+            retention,
+            average_spent,
+            mastery,
+            hardest,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86400;
+    const TODAY: u64 = 20000;
+
+    fn entry(deck: &str, word: &str, grade: Grade, day: u64, spent: u64) -> LogEntry {
+        LogEntry {
+            word: word.to_string(),
+            deck: deck.to_string(),
+            grade,
+            direction: CardDirection::Forward,
+            time: day * DAY + 3600,
+            spent: Duration::from_secs(spent),
+        }
+    }
+
+    fn log() -> Vec<LogEntry> {
+        vec![
+            entry("LSF", "bonjour", Grade::Again, TODAY - 1, 2),
+            entry("LSF", "bonjour", Grade::Good, TODAY, 4),
+            entry("Signes", "bonjour", Grade::Again, TODAY, 6),
+            entry("LSF", "merci", Grade::Again, TODAY, 4),
+            entry("LSF", "merci", Grade::Again, TODAY, 4),
+            // Before the history charts
+            entry("LSF", "merci", Grade::Easy, TODAY - HISTORY_DAYS as u64, 10),
+            entry(NUMBERS_DECK, "12", Grade::Again, TODAY, 30),
+        ]
+    }
+
+    #[test]
+    fn reviews_are_counted_per_day() {
+        let stats = Statistics::from_log(&log(), TODAY, Vec::new());
+        let mut reviews_per_day = vec![0; HISTORY_DAYS];
+        reviews_per_day[HISTORY_DAYS - 2] = 1;
+        reviews_per_day[HISTORY_DAYS - 1] = 4;
+        assert_eq!(stats.reviews_per_day, reviews_per_day);
+        assert_eq!(
+            stats.retention_per_day,
+            vec![((HISTORY_DAYS - 2) as f64, 0.0), ((HISTORY_DAYS - 1) as f64, 25.0)]
+        );
+    }
+
+    #[test]
+    fn totals_leave_numbers_out() {
+        let stats = Statistics::from_log(&log(), TODAY, Vec::new());
+        assert_eq!(stats.total_reviews, 6);
+        assert!((stats.retention - 100.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats.average_spent, Duration::from_secs(5));
+
+        let empty = Statistics::from_log(&[], TODAY, Vec::new());
+        assert_eq!((empty.total_reviews, empty.retention), (0, 0.0));
+        assert_eq!(empty.average_spent, Duration::ZERO);
+        assert!(empty.hardest.is_empty());
+    }
+
+    #[test]
+    fn hardest_words_keep_their_deck() {
+        let stats = Statistics::from_log(&log(), TODAY, Vec::new());
+        let hardest: Vec<(&str, &str, usize, usize)> = stats
+            .hardest
+            .iter()
+            .map(|(deck, word, failures, reviews)| {
+                (deck.as_str(), word.as_str(), *failures, *reviews)
+            })
+            .collect();
+        assert_eq!(
+            hardest,
+            vec![
+                ("LSF", "merci", 2, 3),
+                ("Signes", "bonjour", 1, 1),
+                ("LSF", "bonjour", 1, 2),
+            ]
+        );
+    }
+}
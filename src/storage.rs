@@ -0,0 +1,128 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
+
+// Directory of lsf-tui from an XDG base directory variable,
+// or its default path relative to the home directory
+fn xdg_dir(var: &str, default: &[&str]) -> PathBuf {
+    let base = match env::var_os(var) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = env::var_os("HOME").unwrap_or_default();
+            default.iter().fold(PathBuf::from(home), |path, dir| path.join(dir))
+        }
+    };
+    base.join("lsf-tui")
+}
+
+// Directory holding the state of lsf-tui between two launches,
+// following the XDG base directory specification
+pub fn data_dir() -> PathBuf {
+    xdg_dir("XDG_DATA_HOME", &[".local", "share"])
+}
+
+// Directory holding the settings written by the user
+pub fn config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", &[".config"])
+}
+
+// Directory holding downloaded files that can be fetched again
+pub fn cache_dir() -> PathBuf {
+    xdg_dir("XDG_CACHE_HOME", &[".cache"])
+}
+
+// Number of days since epoch, used as the date of reviews
+pub fn today() -> u64 {
+    now() / 86400
+}
+
+// Number of seconds since epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// Read every document of a yaml state file, none if it does not exist yet
+pub fn load_yaml_stream(path: &Path) -> Result<Vec<Yaml>, io::Error> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    YamlLoader::load_from_str(&contents).map_err(|e| {
+        let info = format!("{}: {}", path.display(), e);
+        io::Error::new(io::ErrorKind::InvalidData, info)
+    })
+}
+
+// Read a yaml state file, `None` if it does not exist yet
+pub fn load_yaml(path: &Path) -> Result<Option<Yaml>, io::Error> {
+    Ok(load_yaml_stream(path)?.into_iter().next())
+}
+
+// Yaml document, starting with its '---' marker
+fn dump_yaml(doc: &Yaml) -> Result<String, io::Error> {
+    let mut contents = String::new();
+    YamlEmitter::new(&mut contents)
+        .dump(doc)
+        .map_err(|e| io::Error::other(format!("{:?}", e)))?;
+    contents.push('\n');
+    Ok(contents)
+}
+
+// Write a yaml state file, the file is replaced atomically
+// so that a crash can't leave it half written
+pub fn save_yaml(path: &Path, doc: &Yaml) -> Result<(), io::Error> {
+    let contents = dump_yaml(doc)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)
+}
+
+// Add a document at the end of a yaml state file, without
+// writing again the documents already there
+pub fn append_yaml(path: &Path, doc: &Yaml) -> Result<(), io::Error> {
+    let contents = dump_yaml(doc)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(contents.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appended_documents_are_read_back_in_order() {
+        let dir = env::temp_dir().join(format!("lsf-tui-storage-{}", std::process::id()));
+        let path = dir.join("log.yaml");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(load_yaml_stream(&path).unwrap().is_empty());
+        for word in ["arbre", "maison"].iter() {
+            let mut h = yaml_rust::yaml::Hash::new();
+            h.insert(Yaml::from_str("word"), Yaml::from_str(word));
+            append_yaml(&path, &Yaml::Hash(h)).unwrap();
+        }
+        let docs = load_yaml_stream(&path).unwrap();
+        let words: Vec<&str> = docs.iter().filter_map(|doc| doc["word"].as_str()).collect();
+        assert_eq!(words, ["arbre", "maison"]);
+        assert_eq!(load_yaml(&path).unwrap().unwrap()["word"].as_str(), Some("arbre"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,82 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// Response of the server to the request of a path
+pub struct Route {
+    pub path: String,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Route {
+    pub fn new(path: &str, content_type: &'static str, body: &[u8]) -> Self {
+        Self {
+            path: path.to_string(),
+            content_type,
+            body: body.to_vec(),
+        }
+    }
+}
+
+// Local HTTP server standing in for the dictionary in tests.
+// Paths without a route are not found.
+pub struct TestServer {
+    pub url: String,
+    // Paths requested so far, in order
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl TestServer {
+    pub fn start(routes: Vec<Route>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let log = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Some(path) = answer(stream, &routes) {
+                    log.lock().unwrap().push(path);
+                }
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    // Number of requests of a path
+    pub fn requests(&self, path: &str) -> usize {
+        self.requests.lock().unwrap().iter().filter(|p| *p == path).count()
+    }
+}
+
+// Read a request and send the response of its path, the connection is closed
+fn answer(mut stream: TcpStream, routes: &[Route]) -> Option<String> {
+    let mut reader = BufReader::new(stream.try_clone().ok()?);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let path = line.split_whitespace().nth(1)?.to_string();
+    // Skip the headers, requests have no body
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).ok()? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let (status, content_type, body) = match routes.iter().find(|route| route.path == path) {
+        Some(route) => ("200 OK", route.content_type, route.body.as_slice()),
+        None => ("404 Not Found", "text/html", &b"not found"[..]),
+    };
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).ok()?;
+    stream.write_all(body).ok()?;
+    Some(path)
+}
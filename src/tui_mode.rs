@@ -1,7 +1,5 @@
-use rand::prelude::*;
-
 use std::io;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 // tui
 use tui::backend::TermionBackend;
@@ -12,16 +10,34 @@ use termion::event::Key;
 use termion::raw::IntoRawMode;
 
 // local modules
-use crate::draw::{draw_dictionary, draw_learn, WordState};
-// use crate::draw::WORDS_LEARN_SIZE;
+use crate::draw::{
+    draw_alphabet, draw_dictionary, draw_learn, draw_statistics, WordState, GRADE_KEYS,
+};
 use crate::event::{Event, Events};
-use crate::loader::{Categorie, Word};
+use crate::loader::{group_words, Categorie, Grouping, Word};
+use crate::numbers::{NumberDeck, NUMBERS_DECK};
+use crate::quiz::LearnMode;
+use crate::review_log::LogEntry;
+use crate::scheduler::{CardDirection, Grade, Scheduler};
+use crate::search::Search;
 use crate::selection::Selection;
+use crate::session::Session;
+use crate::spelling::Spelling;
+use crate::stats::Statistics;
 
 // video search
-use crate::search_video::{query_videos, play_video};
+use crate::search_video::{VideoPlayer, VideoStatus};
+use crate::video_frame::clear_image;
+
+pub fn tui_routine(
+    categories: Vec<Categorie>,
+    alphabet: Vec<Word>,
+    numbers: NumberDeck,
+    mut video: VideoPlayer,
+) -> Result<(), io::Error> {
+    // Review state, history and favourites saved by previous sessions
+    let mut scheduler = Scheduler::load()?;
 
-pub fn tui_routine(categories: Vec<Categorie>, _all_words: Vec<Word>) -> Result<(), io::Error> {
     // Initialize terminal
     let stdout = io::stdout().into_raw_mode()?;
     let backend = TermionBackend::new(stdout);
@@ -33,83 +49,112 @@ pub fn tui_routine(categories: Vec<Categorie>, _all_words: Vec<Word>) -> Result<
     // Get a 'Tick' information every 200ms and get inputs
     let events = Events::new(200);
 
+    // Categories of the files, then words grouped by sign parameters
+    let groupings = group_words(categories);
+
     // Initialize current selection and current tab variable
-    let mut states = Selection::new(categories.len());
+    let mut states = Selection::new(groupings[0].categories.len());
     let mut tab_index = 0;
 
     // Variable to determine if we just swapped between tabs
-    // If swap is 0 we were on tab 0 on last loop and if swap
-    // is 1 we were on tab 1 on last loop.
+    // It holds the index of the tab drawn on last loop.
     let mut swap = 0;
-    let mut begin = Instant::now();
-    let mut words_learn_set: Vec<(&Word, WordState)> = vec![];
-    let mut help = false;
-    let mut time = Duration::new(0, 0);
+    // The session interrupted by quitting, if any, is resumed
+    let mut session = Session::new(&groupings[0].categories, &numbers);
+    session.load()?;
+    let mut stats = Statistics::new(&scheduler, &groupings[0].categories);
+    let mut spelling = Spelling::new(&alphabet, &groupings[0].categories);
 
     loop {
         // Call update function and quit if it return 'Stop'
-        match update(
-            &events,
-            &mut tab_index,
-            &mut states,
-            &categories,
-            &mut help,
-            &mut words_learn_set,
-        ) {
+        let state = if tab_index == 3 {
+            update_alphabet(&events, &mut tab_index, &mut spelling, &mut video)
+        } else {
+            update(
+                &events,
+                &mut tab_index,
+                &mut states,
+                &groupings,
+                &mut session,
+                &mut scheduler,
+                &mut video,
+            )?
+        };
+        match state {
             UpdateState::Stop => break,
             // Refresh the TUI widgets
             UpdateState::Continue => {
+                // Remove the last frame of a video once it is over
+                if video.redraw {
+                    clear_image(terminal.backend_mut(), video.graphics())?;
+                    terminal.clear()?;
+                    video.redraw = false;
+                }
+                // Leaving tab 'Learn' pauses the session
+                if swap == 1 && tab_index != 1 {
+                    pause(&mut session, &mut video);
+                }
+                let grouping = &groupings[states.get_grouping_index()];
+                let categories = &grouping.categories;
                 if tab_index == 0 {
                     // Draw the dictionary mode
-                    if swap == 1 {
+                    if swap != 0 {
                         states.reset();
                     }
-                    draw_dictionary(&mut terminal, &mut states, &categories);
+                    draw_dictionary(
+                        &mut terminal,
+                        &mut states,
+                        categories,
+                        grouping.title,
+                        &mut video,
+                        &scheduler.favourites,
+                    );
                     swap = 0;
                 } else if tab_index == 1 {
-                    // Reset variable because we swap tab
-                    if swap == 0 {
-                        begin = Instant::now();
-                        let mut rng = rand::thread_rng();
-                        // words_set = all_words.iter().choose_multiple(&mut rng, WORDS_LEARN_SIZE);
-                        let cat_index = states.get_categorie_index();
-                        let mut words_set =
-                            categories[cat_index].words.iter().collect::<Vec<&Word>>();
-                        words_set.shuffle(&mut rng);
-                        words_learn_set = words_set
-                            .iter()
-                            .map(|&word| (word, WordState::Next))
-                            .collect();
-
-                        words_learn_set[0].1 = WordState::Current;
-
-                        // Dirty hacks
-                        states.reset_word_index();
-                        states.focus_right(words_set.len());
-
-                        help = false;
+                    // Go back to the session because we swap tab, or choose
+                    // the words of the first one. The categorie highlighted
+                    // in tab 'Dictionary' is offered.
+                    if swap != 1 {
+                        if session.words.is_empty() {
+                            let highlighted = match states.get_grouping_index() {
+                                0 => Some(states.get_categorie_index()),
+                                _ => None,
+                            };
+                            session.open_setup(highlighted);
+                        } else {
+                            session.resume_selection(&mut states);
+                        }
                     }
                     // Calculate time since swap
-                    let now = Instant::now();
-                    if !states.is_done() {
-                        time = now.duration_since(begin);
-                    }
+                    session.update_time(&states);
 
                     // Draw the learn mode
                     draw_learn(
                         &mut terminal,
-                        &mut words_learn_set,
+                        &mut session,
                         &mut states,
-                        &time,
-                        &mut help,
+                        &mut video,
+                        &scheduler.high_scores,
                     );
                     swap = 1;
+                } else if tab_index == 2 {
+                    // Compute statistics again because we swap tab
+                    if swap != 2 {
+                        stats = Statistics::new(&scheduler, &groupings[0].categories);
+                    }
+                    draw_statistics(&mut terminal, &stats);
+                    swap = 2;
+                } else if tab_index == 3 {
+                    draw_alphabet(&mut terminal, &spelling);
+                    swap = 3;
                 }
             }
         }
     }
     terminal.clear()?;
-    Ok(())
+    // Keep the review state and the session in progress for the next launch
+    scheduler.save()?;
+    session.save()
 }
 
 enum UpdateState {
@@ -121,23 +166,69 @@ fn update(
     events: &Events,
     tab_index: &mut usize,
     states: &mut Selection,
-    categories: &[Categorie],
-    help: &mut bool,
-    words_learn_set: &mut Vec<(&Word, WordState)>,
-) -> UpdateState {
+    groupings: &[Grouping],
+    session: &mut Session,
+    scheduler: &mut Scheduler,
+    video: &mut VideoPlayer,
+) -> Result<UpdateState, io::Error> {
     // Try to receive an event, handle it if any, then just return
     if let Ok(x) = events.rx.recv() {
         // An event has been sent, let's handle it
-        // If this event is an input, do some actions
-        if let Event::Input(input) = x {
-            if *tab_index == 0 {
-                return input_tab_one(input, states, tab_index, categories);
-            } else if *tab_index == 1 {
-                return input_tab_two(input, states, help, tab_index, categories, words_learn_set);
-            } else {
-                panic!("Tab index is invalid !")
+        match x {
+            // If this event is an input, do some actions
+            Event::Input(input) => {
+                if *tab_index == 0 {
+                    return input_tab_one(input, states, tab_index, groupings, scheduler, events, video);
+                } else if *tab_index == 1 {
+                    return input_tab_two(input, states, tab_index, session, scheduler, events, video);
+                } else if *tab_index == 2 {
+                    return Ok(input_tab_three(input, tab_index));
+                } else {
+                    panic!("Tab index is invalid !")
+                }
             }
-        };
+            // The video worker made progress
+            Event::Video(lookup, message) => {
+                video.handle(lookup, message, events.tx.clone());
+                // A word whose sign can't be played by the quiz can only be
+                // answered blind, it is skipped without being graded
+                let missing = matches!(video.status, VideoStatus::NotFound(_) | VideoStatus::Failed(..));
+                if video.hidden && missing {
+                    video.stop();
+                    session.skip(states);
+                    end_challenge(states, session, scheduler)?;
+                    quiz_current(session, states, video, events);
+                }
+            }
+            // The countdown of a challenge is over, the word is failed
+            Event::Tick => {
+                let timed_out = session.remaining(states) == Some(Duration::default());
+                if *tab_index == 1 && !session.configuring && !session.is_paused() && timed_out {
+                    let word = session.words[states.get_word_index()].0;
+                    session.answer.time_out(word);
+                    video.stop();
+                    grade_current(Grade::Again, states, session, scheduler)?;
+                    quiz_current(session, states, video, events);
+                }
+            }
+        }
+    }
+    Ok(UpdateState::Continue)
+}
+
+// Events of tab 'Alphabet', letters of the word are played on ticks
+fn update_alphabet(
+    events: &Events,
+    tab_index: &mut usize,
+    spelling: &mut Spelling,
+    video: &mut VideoPlayer,
+) -> UpdateState {
+    if let Ok(x) = events.rx.recv() {
+        match x {
+            Event::Input(input) => return input_tab_four(input, tab_index, spelling),
+            Event::Video(lookup, message) => video.handle(lookup, message, events.tx.clone()),
+            Event::Tick => spelling.tick(),
+        }
     }
     UpdateState::Continue
 }
@@ -146,15 +237,57 @@ fn input_tab_one(
     input: Key,
     states: &mut Selection,
     tab_index: &mut usize,
-    categories: &[Categorie],
-) -> UpdateState {
+    groupings: &[Grouping],
+    scheduler: &mut Scheduler,
+    events: &Events,
+    video: &mut VideoPlayer,
+) -> Result<UpdateState, io::Error> {
+    let categories = &groupings[states.get_grouping_index()].categories;
+
+    // Keys of the video picker and of the playing video,
+    // unless they are typed in the search prompt
+    if states.search.is_none() && video.input(input, events.tx.clone()) {
+        return Ok(UpdateState::Continue);
+    }
+
+    // Keys are typed in the search prompt while it is opened
+    if let Some(search) = states.search.as_mut() {
+        match input {
+            Key::Esc => states.search = None,
+            Key::Char('\n') => {
+                if let Some((cat, word)) = search.selected() {
+                    states.jump(cat, word, categories[cat].words.len());
+                }
+                states.search = None;
+            }
+            Key::Down | Key::Ctrl('n') => search.down(),
+            Key::Up | Key::Ctrl('p') => search.up(),
+            Key::Backspace => {
+                search.query.pop();
+                search.update(categories);
+            }
+            Key::Char(c) => {
+                search.query.push(c);
+                search.update(categories);
+            }
+            _ => {}
+        }
+        return Ok(UpdateState::Continue);
+    }
+
     match input {
         // Change tabs
         Key::Char('2') => {
             *tab_index = 1;
         }
+        Key::Char('3') => {
+            *tab_index = 2;
+        }
+        Key::Char('4') => {
+            *tab_index = 3;
+        }
         // Quit
-        Key::Char('q') => return UpdateState::Stop,
+        Key::Char('q') => return Ok(UpdateState::Stop),
         // Move selection
         Key::Char('j') => {
             states.down();
@@ -168,59 +301,409 @@ fn input_tab_one(
         Key::Char('l') => {
             states.focus_right(categories[states.get_categorie_index()].words.len());
         }
+        // Group words by the next sign parameter
+        Key::Char('g') => {
+            let index = (states.get_grouping_index() + 1) % groupings.len();
+            states.set_grouping(index, groupings[index].categories.len());
+        }
+        // Open search prompt
+        Key::Char('/') => {
+            states.search = Some(Search::new());
+        }
+        // Look for the video of the word without blocking the interface
         Key::Char('v') => {
             let word = &categories[states.get_categorie_index()].words[states.get_word_index()];
-            let urls = query_videos(&word.name.to_string());
-            if !urls.is_empty() {
-                play_video(&urls[0]).unwrap();
-            }
+            video.search(word, events.tx.clone());
+        }
+        // Mark the word as a favourite, or unmark it
+        Key::Char('f') => {
+            let word = &categories[states.get_categorie_index()].words[states.get_word_index()];
+            scheduler.favourites.toggle(word)?;
         }
         // Change tabs
         _ => {}
     };
 
-    UpdateState::Continue
+    Ok(UpdateState::Continue)
 }
 
 fn input_tab_two(
     input: Key,
     states: &mut Selection,
-    help: &mut bool,
     tab_index: &mut usize,
-    categories: &[Categorie],
-    words_learn_set: &mut Vec<(&Word, WordState)>,
-) -> UpdateState {
+    session: &mut Session,
+    scheduler: &mut Scheduler,
+    events: &Events,
+    video: &mut VideoPlayer,
+) -> Result<UpdateState, io::Error> {
+    // Options of the next session are chosen before it starts
+    if session.configuring {
+        return input_setup(input, states, tab_index, session, scheduler, events, video);
+    }
+    // Only a few keys are used while the session is paused
+    if session.is_paused() {
+        match input {
+            Key::Char('p') | Key::Ctrl('s') => {
+                session.resume();
+                quiz_current(session, states, video, events);
+            }
+            // Change tabs
+            Key::Char('1') => {
+                *tab_index = 0;
+            }
+            Key::Char('3') => {
+                *tab_index = 2;
+            }
+            Key::Char('4') => {
+                *tab_index = 3;
+            }
+            // Quit
+            Key::Char('q') => return Ok(UpdateState::Stop),
+            _ => {}
+        }
+        return Ok(UpdateState::Continue);
+    }
+    // The word of the multiple choice quiz is picked with its number
+    if session.mode == LearnMode::Choice && !states.is_done() {
+        return input_choice(input, states, session, scheduler, events, video);
+    }
+    // The answer of the recognition quiz, or of typed mode, is typed
+    if session.mode != LearnMode::Recall && !states.is_done() {
+        return input_answer(input, states, session, scheduler, events, video);
+    }
+
+    // Keys of the video picker and of the playing video
+    if video.input(input, events.tx.clone()) {
+        return Ok(UpdateState::Continue);
+    }
+
+    // Actions of the session summary
+    if states.is_done() {
+        match input {
+            // Retry only the failed words
+            Key::Char('r') => {
+                session.retry_failed(states);
+                quiz_current(session, states, video, events);
+                return Ok(UpdateState::Continue);
+            }
+            // Start a new shuffle of the same words
+            Key::Char('n') => {
+                session.reshuffle(states);
+                quiz_current(session, states, video, events);
+                return Ok(UpdateState::Continue);
+            }
+            _ => {}
+        }
+    }
+
     match input {
         // Change tabs
         Key::Char('1') => {
             *tab_index = 0;
         }
+        Key::Char('3') => {
+            *tab_index = 2;
+        }
+        Key::Char('4') => {
+            *tab_index = 3;
+        }
         // Quit
-        Key::Char('q') => return UpdateState::Stop,
-        // Change word index in learn
-        Key::Char('n') => {
-            let cat_index = states.get_categorie_index();
-
-            // Change word state
-            if words_learn_set[states.get_word_index()].1 != WordState::Failed {
-                words_learn_set[states.get_word_index()].1 = WordState::Valided;
+        Key::Char('q') => return Ok(UpdateState::Stop),
+        // Grade the word and go to the next one, 'n' grades it
+        // 'Good', or 'Hard' if its help was displayed
+        Key::Char(c) if c == 'n' || GRADE_KEYS.iter().any(|(key, _)| *key == c) => {
+            if states.is_done() {
+                return Ok(UpdateState::Continue);
             }
+            let peeked = session.words[states.get_word_index()].1 == WordState::Peeked;
+            let grade = match GRADE_KEYS.iter().find(|(key, _)| *key == c) {
+                Some((_, grade)) => *grade,
+                None if peeked => Grade::Hard,
+                None => Grade::Good,
+            };
 
-            // If the index is over total words in categorie
-            if states.get_word_index() < categories[cat_index].words.len() - 1 {
-                states.down();
-                *help = false;
-                words_learn_set[states.get_word_index()].1 = WordState::Current;
-            } else {
-                states.set_done()
-            }
+            video.stop();
+            grade_current(grade, states, session, scheduler)?;
         }
         // Display help in learn
         Key::Char('h') => {
-            // Change word state
-            words_learn_set[states.get_word_index()].1 = WordState::Failed;
-            *help = !*help;
+            session.toggle_help(states);
+        }
+        // Show the sign of the word, as the help. In the reverse
+        // direction the sign is a clue, played without the word.
+        Key::Char('v') if !states.is_done() => {
+            let word = session.words[states.get_word_index()].0;
+            if session.direction == CardDirection::Reverse {
+                video.quiz(word, events.tx.clone());
+            } else {
+                session.peek(states);
+                video.search(word, events.tx.clone());
+            }
+        }
+        // Ask the word from its description or its sign
+        Key::Char('i') => {
+            video.stop();
+            session.toggle_direction();
+        }
+        // Recognise signs instead of recalling them
+        Key::Char('m') => {
+            session.set_mode(LearnMode::Recognition, states);
+            quiz_current(session, states, video, events);
+        }
+        // Type the word of the description instead of grading oneself
+        Key::Char('t') => {
+            session.set_mode(LearnMode::Typed, states);
+            quiz_current(session, states, video, events);
+        }
+        // Freeze the time of the session
+        Key::Char('p') if !states.is_done() => pause(session, video),
+        // Choose the words of a new session
+        Key::Char('o') => {
+            video.stop();
+            session.open_setup(None);
+        }
+        // Pick the word of the description among a few candidates
+        Key::Char('c') => {
+            session.set_mode(LearnMode::Choice, states);
+            quiz_current(session, states, video, events);
+        }
+        _ => {}
+    };
+
+    Ok(UpdateState::Continue)
+}
+
+fn input_setup(
+    input: Key,
+    states: &mut Selection,
+    tab_index: &mut usize,
+    session: &mut Session,
+    scheduler: &mut Scheduler,
+    events: &Events,
+    video: &mut VideoPlayer,
+) -> Result<UpdateState, io::Error> {
+    let setup = &mut session.setup;
+    match input {
+        Key::Char('j') | Key::Down => setup.down(),
+        Key::Char('k') | Key::Up => setup.up(),
+        // Change the highlighted option, or choose the highlighted categorie
+        Key::Char('h') | Key::Left => setup.change(false),
+        Key::Char('l') | Key::Right | Key::Char(' ') => setup.change(true),
+        // Start the session, unless no word matches the options
+        Key::Char('\n') if session.start_setup(scheduler, states) => {
+            quiz_current(session, states, video, events);
+        }
+        // Go back to the current session, if any
+        Key::Esc if !session.words.is_empty() => session.configuring = false,
+        // Change tabs
+        Key::Char('1') => {
+            *tab_index = 0;
         }
+        Key::Char('3') => {
+            *tab_index = 2;
+        }
+        Key::Char('4') => {
+            *tab_index = 3;
+        }
+        // Quit
+        Key::Char('q') => return Ok(UpdateState::Stop),
+        _ => {}
+    }
+
+    Ok(UpdateState::Continue)
+}
+
+// Grade the current word, go to the next one and record the review
+fn grade_current(
+    grade: Grade,
+    states: &mut Selection,
+    session: &mut Session,
+    scheduler: &mut Scheduler,
+) -> Result<(), io::Error> {
+    let (word, time, spent) = session.grade(grade, states);
+    // Numbers are drawn at random, they are neither scheduled nor logged
+    if word.deck != NUMBERS_DECK {
+        scheduler.review(LogEntry {
+            word: word.name.to_string(),
+            deck: word.deck.to_string(),
+            grade,
+            direction: session.review_direction(),
+            time,
+            spent,
+        })?;
+    }
+    end_challenge(states, session, scheduler)
+}
+
+// The challenge is over, keep its score if it is one of the best
+fn end_challenge(
+    states: &Selection,
+    session: &mut Session,
+    scheduler: &mut Scheduler,
+) -> Result<(), io::Error> {
+    if states.is_done() && session.limit.is_some() {
+        session.rank = scheduler.high_scores.add(&session.challenge, session.score())?;
+    }
+    Ok(())
+}
+
+// Play the sign of the current word without showing the word in the
+// recognition quiz, and in the multiple choice quiz when the word has
+// no description. Stop the quiz video otherwise.
+fn quiz_current(session: &Session, states: &Selection, video: &mut VideoPlayer, events: &Events) {
+    if states.is_done() {
+        if video.hidden {
+            video.stop();
+        }
+        return;
+    }
+    let word = session.words[states.get_word_index()].0;
+    match session.mode {
+        LearnMode::Recognition => video.quiz(word, events.tx.clone()),
+        LearnMode::Choice if word.description.is_empty() => video.quiz(word, events.tx.clone()),
+        _ if video.hidden => video.stop(),
+        _ => {}
+    }
+}
+
+// Pause the session, the sign played by a quiz is played again on resume
+fn pause(session: &mut Session, video: &mut VideoPlayer) {
+    session.pause();
+    if video.hidden {
+        video.stop();
+    }
+}
+
+fn input_choice(
+    input: Key,
+    states: &mut Selection,
+    session: &mut Session,
+    scheduler: &mut Scheduler,
+    events: &Events,
+    video: &mut VideoPlayer,
+) -> Result<UpdateState, io::Error> {
+    let word = session.words[states.get_word_index()].0;
+    match input {
+        // A right pick is graded 'Good' and a wrong one 'Again'
+        Key::Char(c @ '1'..='9') => {
+            let index = c as usize - '1' as usize;
+            if let Some(verdict) = session.answer.choose(index, word) {
+                grade_current(verdict.grade(), states, session, scheduler)?;
+                quiz_current(session, states, video, events);
+            }
+        }
+        // Play the sign as a clue
+        Key::Char('v') => video.quiz(word, events.tx.clone()),
+        Key::Char('p') => pause(session, video),
+        Key::Esc => {
+            session.set_mode(LearnMode::Recall, states);
+            quiz_current(session, states, video, events);
+        }
+        Key::Char('q') => return Ok(UpdateState::Stop),
+        _ => {}
+    }
+
+    Ok(UpdateState::Continue)
+}
+
+fn input_answer(
+    input: Key,
+    states: &mut Selection,
+    session: &mut Session,
+    scheduler: &mut Scheduler,
+    events: &Events,
+    video: &mut VideoPlayer,
+) -> Result<UpdateState, io::Error> {
+    // Words are suggested only when recognising signs
+    let suggestions = match session.mode {
+        LearnMode::Recognition => session.answer.suggestions(&session.words),
+        _ => Vec::new(),
+    };
+
+    match input {
+        // Check the answer, a right answer is graded 'Good',
+        // an answer with a typo 'Hard' and a wrong one 'Again'
+        Key::Char('\n') => {
+            if session.answer.text.trim().is_empty() && session.answer.choice.is_none() {
+                return Ok(UpdateState::Continue);
+            }
+            let word = session.words[states.get_word_index()].0;
+            let grade = session.answer.submit(word, &suggestions).grade();
+            grade_current(grade, states, session, scheduler)?;
+            quiz_current(session, states, video, events);
+        }
+        // Pick one of the suggested words
+        Key::Down | Key::Ctrl('n') => session.answer.down(suggestions.len()),
+        Key::Up | Key::Ctrl('p') => session.answer.up(suggestions.len()),
+        Key::Backspace => session.answer.pop(),
+        // Play the sign again from the beginning, as a clue in typed mode
+        Key::Ctrl('r') => video.quiz(session.words[states.get_word_index()].0, events.tx.clone()),
+        // Letters are typed, the session is paused with a control key
+        Key::Ctrl('s') => pause(session, video),
+        // Clear the answer, or go back to recalling signs. Every
+        // character is typed, tabs are changed once the prompt is left.
+        Key::Esc if session.answer.text.is_empty() => {
+            session.set_mode(LearnMode::Recall, states);
+            quiz_current(session, states, video, events);
+        }
+        Key::Esc => {
+            session.answer.text.clear();
+            session.answer.choice = None;
+        }
+        Key::Char(c) => session.answer.push(c),
+        _ => {}
+    }
+
+    Ok(UpdateState::Continue)
+}
+
+fn input_tab_three(input: Key, tab_index: &mut usize) -> UpdateState {
+    match input {
+        // Change tabs
+        Key::Char('1') => {
+            *tab_index = 0;
+        }
+        Key::Char('2') => {
+            *tab_index = 1;
+        }
+        Key::Char('4') => {
+            *tab_index = 3;
+        }
+        // Quit
+        Key::Char('q') => return UpdateState::Stop,
+        _ => {}
+    };
+
+    UpdateState::Continue
+}
+
+fn input_tab_four(input: Key, tab_index: &mut usize, spelling: &mut Spelling) -> UpdateState {
+    match input {
+        // Change tabs
+        Key::Char('1') => {
+            *tab_index = 0;
+        }
+        Key::Char('2') => {
+            *tab_index = 1;
+        }
+        Key::Char('3') => {
+            *tab_index = 2;
+        }
+        // Quit
+        Key::Char('q') => return UpdateState::Stop,
+        // Show the next letter, or the previous one
+        Key::Char('l') | Key::Right => spelling.forward(),
+        Key::Char('h') | Key::Left => spelling.back(),
+        // Play the letters one after another, or stop
+        Key::Char(' ') => spelling.play(),
+        Key::Char('r') => spelling.restart(),
+        Key::Char('n') => spelling.next(),
+        // Spell words of the decks or names
+        Key::Char('w') => spelling.toggle_source(),
+        // Spell the word before its handshapes are shown
+        Key::Char('s') => spelling.toggle_quiz(),
+        Key::Char('+') => spelling.faster(),
+        Key::Char('-') => spelling.slower(),
         _ => {}
     };
 
@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use reqwest::Url;
+use yaml_rust::yaml::Hash;
+use yaml_rust::Yaml;
+
+use crate::loader::Word;
+use crate::search::normalize;
+use crate::search_video::VideoError;
+use crate::storage::{cache_dir, load_yaml, save_yaml};
+use crate::video_source::{all_videos, Meaning, Video, VideoSource};
+
+// Cached meaning, the videos are file names relative to the cache directory
+fn meaning_to_yaml(meaning: &Meaning) -> Yaml {
+    let videos = meaning
+        .videos
+        .iter()
+        .map(|video| {
+            let mut h = Hash::new();
+            h.insert(Yaml::from_str("file"), Yaml::String(video.uri.to_string()));
+            if let Some(variant) = &video.variant {
+                h.insert(Yaml::from_str("variant"), Yaml::String(variant.to_string()));
+            }
+            Yaml::Hash(h)
+        })
+        .collect();
+
+    let mut h = Hash::new();
+    h.insert(Yaml::from_str("meaning"), Yaml::String(meaning.title.to_string()));
+    h.insert(
+        Yaml::from_str("definition"),
+        Yaml::String(meaning.definition.to_string()),
+    );
+    h.insert(Yaml::from_str("videos"), Yaml::Array(videos));
+    Yaml::Hash(h)
+}
+
+fn meaning_from_yaml(node: &Yaml) -> Option<Meaning> {
+    let videos = node["videos"]
+        .as_vec()?
+        .iter()
+        .map(|video| {
+            Some(Video {
+                uri: video["file"].as_str()?.to_string(),
+                variant: video["variant"].as_str().map(String::from),
+            })
+        })
+        .collect::<Option<Vec<Video>>>()?;
+
+    Some(Meaning {
+        title: node["meaning"].as_str()?.to_string(),
+        definition: node["definition"].as_str()?.to_string(),
+        videos,
+    })
+}
+
+// Offline copy of the dictionary videos, indexed by word.
+// The directory holds the mp4 files and an index file.
+pub struct VideoCache {
+    dir: PathBuf,
+    // Meanings of each word, with the video files relative to the directory
+    index: HashMap<String, Vec<Meaning>>,
+}
+
+impl VideoCache {
+    pub fn default_dir() -> PathBuf {
+        cache_dir().join("videos")
+    }
+
+    pub fn open(dir: PathBuf) -> Result<Self, io::Error> {
+        let mut index = HashMap::new();
+
+        if let Some(doc) = load_yaml(&dir.join("index.yaml"))? {
+            if let Some(words) = doc["words"].as_hash() {
+                for (name, meanings) in words {
+                    let meanings = meanings
+                        .as_vec()
+                        .and_then(|m| m.iter().map(meaning_from_yaml).collect());
+                    if let (Some(name), Some(meanings)) = (name.as_str(), meanings) {
+                        index.insert(name.to_string(), meanings);
+                    }
+                }
+            }
+        }
+
+        Ok(Self { dir, index })
+    }
+
+    fn save(&self) -> Result<(), io::Error> {
+        // Sort words to keep the file readable
+        let mut names: Vec<&String> = self.index.keys().collect();
+        names.sort();
+
+        let mut words = Hash::new();
+        for name in names {
+            let meanings = self.index[name].iter().map(meaning_to_yaml).collect();
+            words.insert(Yaml::String(name.to_string()), Yaml::Array(meanings));
+        }
+        let mut doc = Hash::new();
+        doc.insert(Yaml::from_str("words"), Yaml::Hash(words));
+        save_yaml(&self.dir.join("index.yaml"), &Yaml::Hash(doc))
+    }
+
+    // Cached meanings of a word with `file://` URIs, `None` if the word
+    // was never fetched or if one of its files has been removed
+    pub fn get(&self, word: &str) -> Option<Vec<Meaning>> {
+        self.index
+            .get(word)?
+            .iter()
+            .map(|meaning| {
+                let videos = meaning
+                    .videos
+                    .iter()
+                    .map(|video| {
+                        Some(Video {
+                            uri: file_uri(&self.dir.join(&video.uri))?,
+                            variant: video.variant.clone(),
+                        })
+                    })
+                    .collect::<Option<Vec<Video>>>()?;
+                Some(Meaning {
+                    videos,
+                    ..meaning.clone()
+                })
+            })
+            .collect()
+    }
+
+    // Keep the meanings of a word, with the video files
+    // relative to the directory, and save the index
+    fn insert(&mut self, word: &str, meanings: Vec<Meaning>) -> Result<(), io::Error> {
+        self.index.insert(word.to_string(), meanings);
+        self.save()
+    }
+}
+
+// FNV-1a hash of a word, stable from one version of lsf-tui to another
+fn hash(word: &str) -> u32 {
+    word.bytes()
+        .fold(0x811c_9dc5, |hash, byte| (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193))
+}
+
+// Query the source, download the videos of a word and return its meanings
+// with `file://` URIs. The cache is only locked to read and update its index,
+// other lookups go on during the download. A word without videos is not
+// cached, they may be added to the dictionary later.
+pub fn fetch(
+    cache: &Mutex<VideoCache>,
+    source: &dyn VideoSource,
+    word: &Word,
+) -> Result<Vec<Meaning>, VideoError> {
+    let mut meanings = source.meanings(word)?;
+    if meanings.is_empty() {
+        return Ok(meanings);
+    }
+    let dir = cache.lock().unwrap().dir.clone();
+    fs::create_dir_all(&dir)?;
+
+    // Files are named after the word, without accents nor spaces, and
+    // its hash tells apart the words only differing by their accents
+    let mut stem = normalize(&word.name).replace(' ', "-");
+    if stem.is_empty() {
+        stem = String::from("video");
+    }
+    let videos = meanings.iter_mut().flat_map(|m| m.videos.iter_mut());
+    for (i, video) in videos.enumerate() {
+        let file = format!("{}-{:08x}-{}.mp4", stem, hash(&word.name), i);
+        download(&video.uri, &dir.join(&file))?;
+        video.uri = file;
+    }
+
+    let mut cache = cache.lock().unwrap();
+    cache.insert(&word.name, meanings)?;
+    cache
+        .get(&word.name)
+        .ok_or_else(|| VideoError::Io(io::Error::other("cached video missing")))
+}
+
+// Meanings of a word from the source, through the offline cache if the
+// source is worth caching
+pub fn find_meanings(
+    source: &dyn VideoSource,
+    cache: Option<&Mutex<VideoCache>>,
+    word: &Word,
+) -> Result<Vec<Meaning>, VideoError> {
+    let cache = match cache {
+        Some(cache) => cache,
+        None => return source.meanings(word),
+    };
+    let cached = cache.lock().unwrap().get(&word.name);
+    match cached {
+        Some(meanings) => Ok(meanings),
+        None => fetch(cache, source, word),
+    }
+}
+
+fn file_uri(path: &Path) -> Option<String> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(path)
+    };
+    if !path.is_file() {
+        return None;
+    }
+    Url::from_file_path(path).ok().map(String::from)
+}
+
+// Download a file, it only appears once complete. Each download has
+// its own temporary file, the same word may be fetched twice at once.
+fn download(url: &str, path: &Path) -> Result<(), VideoError> {
+    static DOWNLOADS: AtomicUsize = AtomicUsize::new(0);
+    let mut response = reqwest::blocking::get(url)?.error_for_status()?;
+    let tmp = path.with_extension(format!("{}.tmp", DOWNLOADS.fetch_add(1, Ordering::Relaxed)));
+    let mut file = fs::File::create(&tmp)?;
+    response.copy_to(&mut file)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+// Fill the cache with the videos of every word that is not cached yet
+pub fn prefetch_routine(
+    source: &dyn VideoSource,
+    cache: Option<&Mutex<VideoCache>>,
+    mut all_words: Vec<Word>,
+) -> Result<(), io::Error> {
+    let cache = match cache {
+        Some(cache) => cache,
+        None => {
+            println!("Videos of the {} source are not cached", source.name());
+            return Ok(());
+        }
+    };
+    all_words.sort_by(|a, b| a.name.cmp(&b.name));
+    all_words.dedup_by(|a, b| a.name == b.name);
+
+    let mut failures = 0;
+    for (i, word) in all_words.iter().enumerate() {
+        let progress = format!("[{}/{}] {}", i + 1, all_words.len(), word.name);
+        if cache.lock().unwrap().get(&word.name).is_some() {
+            println!("{} : already cached", progress);
+            continue;
+        }
+        match fetch(cache, source, word) {
+            Ok(meanings) => println!(
+                "{} : {} meanings, {} videos",
+                progress,
+                meanings.len(),
+                all_videos(&meanings).len()
+            ),
+            Err(e) => {
+                eprintln!("{} : {}", progress, e);
+                failures += 1;
+            }
+        }
+    }
+
+    println!(
+        "{} words cached in {}",
+        all_words.len() - failures,
+        cache.lock().unwrap().dir.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::test_server::{Route, TestServer};
+    use crate::video_source::LinkSource;
+
+    fn temp_cache(name: &str) -> (PathBuf, Mutex<VideoCache>) {
+        let dir = std::env::temp_dir().join(format!("lsf-tui-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = VideoCache::open(dir.clone()).unwrap();
+        (dir, Mutex::new(cache))
+    }
+
+    #[test]
+    fn fetched_videos_are_served_from_the_cache() {
+        let server = TestServer::start(vec![Route::new("/arbre.mp4", "video/mp4", b"arbre")]);
+        let (dir, cache) = temp_cache("cache-fetch");
+        let arbre = Word { link: format!("{}/arbre.mp4", server.url), ..Word::named("arbre") };
+
+        let meanings = find_meanings(&LinkSource, Some(&cache), &arbre).unwrap();
+        assert_eq!(server.requests("/arbre.mp4"), 1);
+        let uri = Url::parse(&meanings[0].videos[0].uri).unwrap();
+        assert_eq!(fs::read(uri.to_file_path().unwrap()).unwrap(), b"arbre");
+
+        // Later lookups, and the index on disk, don't query the source again
+        find_meanings(&LinkSource, Some(&cache), &arbre).unwrap();
+        assert!(VideoCache::open(dir.clone()).unwrap().get("arbre").is_some());
+        assert_eq!(server.requests("/arbre.mp4"), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn words_without_videos_are_not_cached() {
+        let (dir, cache) = temp_cache("cache-empty");
+        let meanings = find_meanings(&LinkSource, Some(&cache), &Word::named("arbre"));
+        assert!(meanings.unwrap().is_empty());
+        assert!(cache.lock().unwrap().get("arbre").is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn words_differing_by_accents_keep_their_own_files() {
+        let server = TestServer::start(vec![
+            Route::new("/peche.mp4", "video/mp4", b"peche"),
+            Route::new("/pecher.mp4", "video/mp4", b"pecher"),
+        ]);
+        let (dir, cache) = temp_cache("cache-accents");
+        let link = |file: &str| format!("{}/{}.mp4", server.url, file);
+        let peche = Word { link: link("peche"), ..Word::named("pêche") };
+        let peche_sin = Word { link: link("pecher"), ..Word::named("péché") };
+
+        fetch(&cache, &LinkSource, &peche).unwrap();
+        fetch(&cache, &LinkSource, &peche_sin).unwrap();
+        let cache = cache.lock().unwrap();
+        for (name, body) in [("pêche", b"peche".to_vec()), ("péché", b"pecher".to_vec())].iter() {
+            let uri = Url::parse(&cache.get(name).unwrap()[0].videos[0].uri).unwrap();
+            assert_eq!(&fs::read(uri.to_file_path().unwrap()).unwrap(), body);
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_lookups_share_the_index() {
+        let names = ["un", "deux", "trois", "quatre"];
+        let server = TestServer::start(
+            names
+                .iter()
+                .map(|name| Route::new(&format!("/{}.mp4", name), "video/mp4", name.as_bytes()))
+                .collect(),
+        );
+        let (dir, cache) = temp_cache("cache-threads");
+        let cache = Arc::new(cache);
+
+        let workers: Vec<_> = names
+            .iter()
+            .map(|name| {
+                let cache = Arc::clone(&cache);
+                let link = format!("{}/{}.mp4", server.url, name);
+                let word = Word { link, ..Word::named(name) };
+                thread::spawn(move || find_meanings(&LinkSource, Some(&cache), &word).unwrap())
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        let index = VideoCache::open(dir.clone()).unwrap();
+        assert!(names.iter().all(|name| index.get(name).is_some()));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
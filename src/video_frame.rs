@@ -0,0 +1,274 @@
+use std::env;
+use std::io::{self, Write};
+
+use termion::cursor::Goto;
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::Color;
+use tui::widgets::Widget;
+
+// Decoded image of a video, in RGB with three bytes per pixel
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    // Bytes of a row, which may be padded
+    pub stride: usize,
+    pub data: Vec<u8>,
+}
+
+impl Frame {
+    fn pixel(&self, x: usize, y: usize) -> (u8, u8, u8) {
+        let i = y * self.stride + x * 3;
+        (self.data[i], self.data[i + 1], self.data[i + 2])
+    }
+
+    // Pixel of the frame scaled to `width` x `height`, nearest neighbour
+    fn scaled_pixel(&self, x: usize, y: usize, width: usize, height: usize) -> (u8, u8, u8) {
+        self.pixel(x * self.width / width, y * self.height / height)
+    }
+
+    // Largest size with the aspect ratio of the frame that fits in the box
+    fn fit(&self, width: usize, height: usize) -> (usize, usize) {
+        if self.width * height > self.height * width {
+            (width, (self.height * width / self.width).max(1))
+        } else {
+            ((self.width * height / self.height).max(1), height)
+        }
+    }
+}
+
+// How frames are drawn in the terminal
+#[derive(Clone, Copy, PartialEq)]
+pub enum Graphics {
+    // Two pixels per cell with the upper half block character
+    HalfBlocks,
+    Sixel,
+    // Graphics protocol of the kitty terminal
+    Kitty,
+}
+
+impl Graphics {
+    // Best protocol supported by the terminal, guessed from its environment
+    pub fn detect() -> Self {
+        let term = env::var("TERM").unwrap_or_default();
+        if env::var_os("KITTY_WINDOW_ID").is_some() || term.contains("kitty") {
+            Graphics::Kitty
+        } else if ["foot", "mlterm", "yaft", "contour", "sixel"]
+            .iter()
+            .any(|name| term.contains(name))
+        {
+            Graphics::Sixel
+        } else {
+            Graphics::HalfBlocks
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(Graphics::detect()),
+            "halfblocks" => Some(Graphics::HalfBlocks),
+            "sixel" => Some(Graphics::Sixel),
+            "kitty" => Some(Graphics::Kitty),
+            _ => None,
+        }
+    }
+}
+
+// Widget drawing a frame with half blocks, centered in its area
+pub struct HalfBlocks<'a> {
+    frame: &'a Frame,
+}
+
+impl<'a> HalfBlocks<'a> {
+    pub fn new(frame: &'a Frame) -> Self {
+        Self { frame }
+    }
+}
+
+impl<'a> Widget for HalfBlocks<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        // A cell is about twice as high as wide, so its halves are square
+        let (width, height) = self
+            .frame
+            .fit(area.width as usize, area.height as usize * 2);
+        let left = area.left() + (area.width - width as u16) / 2;
+        let top = area.top() + (area.height - height.div_ceil(2) as u16) / 2;
+
+        for y in 0..height.div_ceil(2) {
+            for x in 0..width {
+                let (r, g, b) = self.frame.scaled_pixel(x, y * 2, width, height);
+                let cell = buf.get_mut(left + x as u16, top + y as u16);
+                cell.set_symbol("▀").set_fg(Color::Rgb(r, g, b));
+                if y * 2 + 1 < height {
+                    let (r, g, b) = self.frame.scaled_pixel(x, y * 2 + 1, width, height);
+                    cell.set_bg(Color::Rgb(r, g, b));
+                }
+            }
+        }
+    }
+}
+
+// Size of a cell in pixels, a common size when the terminal doesn't tell
+fn cell_size() -> (usize, usize) {
+    match (
+        termion::terminal_size(),
+        termion::terminal_size_pixels(),
+    ) {
+        (Ok((cols, rows)), Ok((width, height))) if cols > 0 && rows > 0 && width > 0 => {
+            (width as usize / cols as usize, height as usize / rows as usize)
+        }
+        _ => (8, 16),
+    }
+}
+
+// Sixel image of the frame scaled to fit in `width` x `height` pixels.
+// Colors are reduced to a 6x6x6 cube.
+fn sixel(frame: &Frame, width: usize, height: usize) -> String {
+    let (width, height) = frame.fit(width, height);
+    let level = |c: u8| (c as usize * 5 + 127) / 255;
+    let colors: Vec<usize> = (0..width * height)
+        .map(|i| {
+            let (r, g, b) = frame.scaled_pixel(i % width, i / width, width, height);
+            level(r) * 36 + level(g) * 6 + level(b)
+        })
+        .collect();
+
+    // Palette, in percents
+    let mut out = format!("\x1bPq\"1;1;{};{}", width, height);
+    for color in 0..216 {
+        let percent = |l: usize| l * 100 / 5;
+        out.push_str(&format!(
+            "#{};2;{};{};{}",
+            color,
+            percent(color / 36),
+            percent(color / 6 % 6),
+            percent(color % 6)
+        ));
+    }
+
+    // Each band of six rows is drawn once per color it contains
+    for band in (0..height).step_by(6) {
+        let rows = (height - band).min(6);
+        let mut used = [false; 216];
+        for y in band..band + rows {
+            for x in 0..width {
+                used[colors[y * width + x]] = true;
+            }
+        }
+        for color in (0..216).filter(|c| used[*c]) {
+            out.push_str(&format!("#{}", color));
+            // Run length encoding of the sixels of the color
+            let mut run: Option<(char, usize)> = None;
+            for x in 0..width {
+                let bits = (0..rows)
+                    .filter(|dy| colors[(band + dy) * width + x] == color)
+                    .fold(0, |bits, dy| bits | 1 << dy);
+                let sixel = (63 + bits) as u8 as char;
+                run = match run {
+                    Some((c, n)) if c == sixel => Some((c, n + 1)),
+                    Some((c, n)) => {
+                        push_run(&mut out, c, n);
+                        Some((sixel, 1))
+                    }
+                    None => Some((sixel, 1)),
+                };
+            }
+            if let Some((c, n)) = run {
+                push_run(&mut out, c, n);
+            }
+            // Back to the beginning of the band for the next color
+            out.push('$');
+        }
+        // Next band
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn push_run(out: &mut String, sixel: char, count: usize) {
+    if count > 3 {
+        out.push_str(&format!("!{}{}", count, sixel));
+    } else {
+        out.extend(std::iter::repeat_n(sixel, count));
+    }
+}
+
+// Kitty image of the frame, scaled by the terminal to the cells.
+// The image replaces the previous one, which has the same id.
+fn kitty(frame: &Frame, cols: u16, rows: u16) -> String {
+    // Rows without padding
+    let mut data = Vec::with_capacity(frame.width * frame.height * 3);
+    for y in 0..frame.height {
+        let row = y * frame.stride;
+        data.extend_from_slice(&frame.data[row..row + frame.width * 3]);
+    }
+    let encoded = base64::encode(&data);
+
+    // The payload is sent in chunks of 4096 bytes
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        if i == 0 {
+            out.push_str(&format!(
+                "\x1b_Ga=T,f=24,s={},v={},i=1,c={},r={},C=1,q=2,m={};",
+                frame.width, frame.height, cols, rows, more
+            ));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};", more));
+        }
+        out.push_str(&String::from_utf8_lossy(chunk));
+        out.push_str("\x1b\\");
+    }
+    out
+}
+
+// Draw a frame in an area with a graphics protocol, the cells of the area
+// must have been left blank by tui
+pub fn write_image<W: Write>(
+    out: &mut W,
+    graphics: Graphics,
+    frame: &Frame,
+    area: Rect,
+) -> Result<(), io::Error> {
+    if area.width == 0 || area.height == 0 {
+        return Ok(());
+    }
+    let image = match graphics {
+        Graphics::HalfBlocks => return Ok(()),
+        Graphics::Sixel => {
+            let (cell_width, cell_height) = cell_size();
+            sixel(
+                frame,
+                area.width as usize * cell_width,
+                area.height as usize * cell_height,
+            )
+        }
+        Graphics::Kitty => {
+            // The terminal stretches the image to the cells
+            let (cell_width, cell_height) = cell_size();
+            let (width, height) = frame.fit(
+                area.width as usize * cell_width,
+                area.height as usize * cell_height,
+            );
+            let cols = width.div_ceil(cell_width);
+            let rows = height.div_ceil(cell_height);
+            kitty(frame, cols as u16, rows as u16)
+        }
+    };
+    write!(out, "{}{}", Goto(area.x + 1, area.y + 1), image)?;
+    out.flush()
+}
+
+// Remove the image drawn by the kitty protocol
+pub fn clear_image<W: Write>(out: &mut W, graphics: Option<Graphics>) -> Result<(), io::Error> {
+    if graphics == Some(Graphics::Kitty) {
+        write!(out, "\x1b_Ga=d,d=i,i=1,q=2\x1b\\")?;
+        out.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,293 @@
+use reqwest::Url;
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::elix::parse_page;
+use crate::loader::Word;
+use crate::search::normalize;
+use crate::search_video::VideoError;
+
+// Elix dictionary of LSF
+pub const ELIX_URL: &str = "https://dico.elix-lsf.fr";
+
+// Extensions of the video files of a local directory or of a link
+const VIDEO_EXTENSIONS: [&str; 6] = ["mp4", "webm", "mkv", "ogv", "avi", "mov"];
+
+// Video of a sign, playable by gstreamer
+#[derive(Clone)]
+pub struct Video {
+    pub uri: String,
+    // Regional or alternative variant shown by the video
+    pub variant: Option<String>,
+}
+
+// A sense of the word with its definition and the videos of its signs
+#[derive(Clone)]
+pub struct Meaning {
+    pub title: String,
+    pub definition: String,
+    pub videos: Vec<Video>,
+}
+
+// Every video of the meanings, in order
+pub fn all_videos(meanings: &[Meaning]) -> Vec<&Video> {
+    meanings.iter().flat_map(|m| m.videos.iter()).collect()
+}
+
+// Single meaning of a word, for sources without definitions
+fn word_meaning(word: &Word, uris: Vec<String>) -> Vec<Meaning> {
+    if uris.is_empty() {
+        return Vec::new();
+    }
+    vec![Meaning {
+        title: word.name.to_string(),
+        definition: word.description.to_string(),
+        videos: uris
+            .into_iter()
+            .map(|uri| Video { uri, variant: None })
+            .collect(),
+    }]
+}
+
+// Provider of the videos of a word
+pub trait VideoSource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // Meanings of a word with the videos of their signs
+    fn meanings(&self, word: &Word) -> Result<Vec<Meaning>, VideoError>;
+
+    // Remote videos are worth keeping in the offline cache
+    fn cacheable(&self) -> bool {
+        false
+    }
+}
+
+// Videos scraped from the pages of the Elix dictionary
+pub struct ElixSource {
+    base_url: String,
+}
+
+impl ElixSource {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    // Page of a word, its name is a single segment of the path whatever
+    // its spaces, slashes or question marks
+    fn page(&self, word: &str) -> Result<Url, VideoError> {
+        let invalid = || {
+            let info = format!("invalid dictionary URL: {}", self.base_url);
+            io::Error::new(io::ErrorKind::InvalidInput, info)
+        };
+        let mut url = Url::parse(&self.base_url).map_err(|_| invalid())?;
+        url.path_segments_mut()
+            .map_err(|_| invalid())?
+            .pop_if_empty()
+            .extend(&["dictionnaire", word]);
+        Ok(url)
+    }
+}
+
+impl VideoSource for ElixSource {
+    fn name(&self) -> &'static str {
+        "elix"
+    }
+
+    fn meanings(&self, word: &Word) -> Result<Vec<Meaning>, VideoError> {
+        let response = reqwest::blocking::get(self.page(&word.name)?)?;
+        // Unknown words have no page
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        let response = response.error_for_status()?;
+        let page = response.url().clone();
+        let body = response.text()?;
+
+        let meanings = parse_page(&word.name, &page, &body);
+
+        // Videos that can't be extracted mean that the markup has changed
+        if meanings.is_empty() && body.contains("<video") {
+            return Err(VideoError::Markup(page.to_string()));
+        }
+
+        Ok(meanings)
+    }
+
+    fn cacheable(&self) -> bool {
+        true
+    }
+}
+
+// Video files of a local directory, named after their word
+// ("Bonjour.mp4", "bonjour-2.webm", ...)
+pub struct LocalSource {
+    dir: PathBuf,
+}
+
+impl LocalSource {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+impl VideoSource for LocalSource {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn meanings(&self, word: &Word) -> Result<Vec<Meaning>, VideoError> {
+        let name = normalize(&word.name);
+        let mut paths = Vec::new();
+
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if !VIDEO_EXTENSIONS.contains(&extension.to_lowercase().as_str()) {
+                continue;
+            }
+            // The word may be followed by the number of the video
+            let stem = normalize(&path.file_stem().unwrap_or_default().to_string_lossy());
+            let number = stem.strip_prefix(&name).map(str::trim_start);
+            if let Some(number) = number {
+                if number.chars().all(|c| c.is_ascii_digit()) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths.sort();
+
+        let dir = fs::canonicalize(&self.dir)?;
+        let uris = paths
+            .iter()
+            .filter_map(|path| Url::from_file_path(dir.join(path.file_name()?)).ok())
+            .map(String::from)
+            .collect();
+        Ok(word_meaning(word, uris))
+    }
+}
+
+// Link given in the words file, if it leads to a video
+pub struct LinkSource;
+
+// The link is a video file, known by its extension or, for a web
+// link without one, by the content type its server announces.
+// Links to the pages of a dictionary are not videos.
+fn is_video(url: &Url) -> Result<bool, VideoError> {
+    let extension = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .and_then(|file| file.rsplit_once('.'))
+        .map(|(_, extension)| extension.to_lowercase());
+    if let Some(extension) = extension {
+        if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+            return Ok(true);
+        }
+    }
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Ok(false);
+    }
+
+    let response = reqwest::blocking::Client::new().head(url.clone()).send()?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    Ok(response.status().is_success() && content_type.starts_with("video/"))
+}
+
+impl VideoSource for LinkSource {
+    fn name(&self) -> &'static str {
+        "link"
+    }
+
+    fn meanings(&self, word: &Word) -> Result<Vec<Meaning>, VideoError> {
+        // Text that is not a link has no video, a link to a web page such
+        // as YouTube is refused
+        match Url::parse(word.link.trim()) {
+            Ok(url) if is_video(&url)? => Ok(word_meaning(word, vec![String::from(url)])),
+            Ok(url) => Err(VideoError::NotVideo(url.to_string())),
+            Err(_) => Ok(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_server::{Route, TestServer};
+
+    // Page of a word in the dictionary, as served by Elix
+    const PAGE: &str = r#"<html><body><h1>Bonjour</h1>
+<video src="https://www.elix-lsf.fr/IMG/mp4/bonjour.mp4"></video>
+</body></html>"#;
+
+    #[test]
+    fn elix_source_reads_the_videos_of_the_page() {
+        let server = TestServer::start(vec![
+            Route::new("/dictionnaire/bonjour", "text/html", PAGE.as_bytes()),
+            Route::new("/dictionnaire/nouveau", "text/html", b"<video class=\"player\"></video>"),
+        ]);
+        let elix = ElixSource::new(&server.url);
+
+        let meanings = elix.meanings(&Word::named("bonjour")).unwrap();
+        let uris: Vec<&str> = all_videos(&meanings).iter().map(|v| v.uri.as_str()).collect();
+        assert_eq!(uris, ["https://www.elix-lsf.fr/IMG/mp4/bonjour.mp4"]);
+
+        // Unknown words have no page, pages with unreadable videos are errors
+        assert!(elix.meanings(&Word::named("inconnu")).unwrap().is_empty());
+        assert!(matches!(
+            elix.meanings(&Word::named("nouveau")),
+            Err(VideoError::Markup(_))
+        ));
+    }
+
+    #[test]
+    fn elix_pages_are_named_after_the_encoded_word() {
+        let elix = ElixSource::new("https://dico.elix-lsf.fr/");
+        let page = |word: &str| elix.page(word).unwrap().to_string();
+        assert_eq!(page("bonjour"), "https://dico.elix-lsf.fr/dictionnaire/bonjour");
+        assert_eq!(
+            page("s'il vous plaît"),
+            "https://dico.elix-lsf.fr/dictionnaire/s'il%20vous%20pla%C3%AEt"
+        );
+        assert_eq!(page("1/2 ?#"), "https://dico.elix-lsf.fr/dictionnaire/1%2F2%20%3F%23");
+
+        let server = TestServer::start(vec![Route::new(
+            "/dictionnaire/pomme%20de%20terre",
+            "text/html",
+            PAGE.as_bytes(),
+        )]);
+        let meanings = ElixSource::new(&server.url)
+            .meanings(&Word::named("pomme de terre"))
+            .unwrap();
+        assert_eq!(all_videos(&meanings).len(), 1);
+    }
+
+    #[test]
+    fn link_source_only_plays_videos() {
+        let server = TestServer::start(vec![
+            Route::new("/dictionnaire/bonjour", "text/html", PAGE.as_bytes()),
+            Route::new("/signes/bonjour", "video/mp4", b"mp4"),
+        ]);
+        let meanings = |link: String| LinkSource.meanings(&Word { link, ..Word::named("bonjour") });
+        let videos = |link: String| all_videos(&meanings(link).unwrap()).len();
+
+        // Known extensions are trusted without asking the server
+        assert_eq!(videos(format!("{}/videos/Bonjour.MP4", server.url)), 1);
+        assert_eq!(server.requests("/videos/Bonjour.MP4"), 0);
+        assert_eq!(videos(format!("{}/signes/bonjour", server.url)), 1);
+        assert_eq!(videos(String::from("Some link providing more information")), 0);
+
+        // Pages are refused rather than left without video
+        let page = format!("{}/dictionnaire/bonjour", server.url);
+        assert!(matches!(meanings(page), Err(VideoError::NotVideo(_))));
+        let watch = format!("{}/watch?v=bonjour", server.url);
+        assert!(matches!(meanings(watch), Err(VideoError::NotVideo(_))));
+    }
+}